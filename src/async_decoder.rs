@@ -0,0 +1,188 @@
+//decoding from tokio readers, for assets served over the network.
+use std::io::{Cursor, SeekFrom};
+
+use futures_core::Stream;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncSeek, AsyncSeekExt};
+
+use crate::decoder_core::block;
+use crate::error::CksError;
+use crate::file_header::{self, HEADER_BYTES};
+use crate::sample::info::SampleInfo;
+
+pub struct AsyncDecoder<R>
+where
+    R: AsyncRead + AsyncSeek + Unpin,
+{
+    reader: R,
+    sample_info: SampleInfo,
+    stream_size: u64,
+    position: u64,
+    reader_buf: Vec<u8>,
+    block_buf: Vec<i16>,
+}
+
+impl<R> AsyncDecoder<R>
+where
+    R: AsyncRead + AsyncSeek + Unpin,
+{
+    pub async fn new(mut reader: R) -> Result<Self, CksError> {
+        let stream_size = reader.seek(SeekFrom::End(0)).await.or(Err(CksError::Io))?;
+        reader.rewind().await.or(Err(CksError::Io))?;
+        let mut head = [0_u8; HEADER_BYTES];
+        reader
+            .read_exact(&mut head)
+            .await
+            .or(Err(CksError::FileRead))?;
+        let sample_info = file_header::parse_header(&head)?;
+        let reader_buf = Vec::with_capacity(sample_info.block_bytes as usize);
+        Ok(Self {
+            reader,
+            sample_info,
+            stream_size,
+            position: HEADER_BYTES as u64,
+            reader_buf,
+            block_buf: Vec::new(),
+        })
+    }
+
+    pub fn sample_info(&self) -> SampleInfo {
+        self.sample_info.clone()
+    }
+
+    //decodes the next block into interleaved f32 whatever the stored format is.
+    //returns amount of frames decoded, None at the end of the stream.
+    //stops after the header's block count, or at the end of the stream when that is unknown.
+    pub async fn decode_f32(&mut self, buf: &mut Vec<f32>) -> Result<Option<usize>, CksError> {
+        let block_bytes = self.sample_info.block_bytes as u64;
+        let blocks = u64::try_from(self.sample_info.blocks).ok();
+        let block = (self.position - HEADER_BYTES as u64) / block_bytes.max(1);
+        if blocks.is_some_and(|blocks| block >= blocks) {
+            return Ok(None);
+        }
+        let bytes = std::cmp::min(block_bytes, self.stream_size.saturating_sub(self.position));
+        if bytes == 0 && blocks.is_none() {
+            return Ok(None);
+        }
+        if bytes < block_bytes && blocks.is_some() {
+            return Err(CksError::InsufficientData);
+        }
+        self.reader_buf.resize(bytes as usize, 0);
+        self.reader
+            .read_exact(&mut self.reader_buf)
+            .await
+            .or(Err(CksError::Io))?;
+        self.position += bytes;
+        let frames = block::decode_blocks_f32(
+            &self.sample_info.format,
+            self.sample_info.channels as usize,
+            block_bytes as usize,
+            &self.reader_buf,
+            buf,
+            &mut self.block_buf,
+        )?;
+        Ok((frames > 0).then_some(frames))
+    }
+
+    //block starts with 0. positions past the end of the stream end up at the end.
+    pub async fn set_block_pos(&mut self, block: u64) -> Result<(), CksError> {
+        let pos = (HEADER_BYTES as u64)
+            .saturating_add(block.saturating_mul(self.sample_info.block_bytes as u64))
+            .min(self.stream_size);
+        self.position = self
+            .reader
+            .seek(SeekFrom::Start(pos))
+            .await
+            .or(Err(CksError::Io))?;
+        Ok(())
+    }
+
+    //decoded blocks as interleaved f32, ending after the last block or the first error.
+    pub fn into_stream(self) -> impl Stream<Item = Result<Vec<f32>, CksError>> {
+        futures_util::stream::unfold(Some(self), |decoder| async move {
+            let mut decoder = decoder?;
+            let mut buf = Vec::new();
+            match decoder.decode_f32(&mut buf).await {
+                Ok(Some(_)) => Some((Ok(buf), Some(decoder))),
+                Ok(None) => None,
+                Err(e) => Some((Err(e), None)),
+            }
+        })
+    }
+
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+}
+
+#[cfg(test)]
+fn block_on<F: std::future::Future>(f: F) -> F::Output {
+    tokio::runtime::Builder::new_current_thread()
+        .build()
+        .unwrap()
+        .block_on(f)
+}
+
+#[test]
+fn async_decode_matches_sync() {
+    let samples = (0..300).map(|i| (i * 100) as i16).collect::<Vec<_>>();
+    let file = crate::test_util::pcm16_cks(2, 22050, &samples, (0, 0, 0));
+    let mut sync = crate::decoder::Decoder::new(Cursor::new(file.clone())).unwrap();
+    let mut expected = Vec::new();
+    let mut buf = Vec::new();
+    while sync.decode_f32(&mut buf).unwrap().is_some() {
+        expected.extend_from_slice(&buf);
+    }
+    let decoded = block_on(async {
+        let mut decoder = AsyncDecoder::new(Cursor::new(file)).await.unwrap();
+        assert_eq!(decoder.sample_info().channels, 2);
+        let mut out = Vec::new();
+        let mut buf = Vec::new();
+        while let Some(frames) = decoder.decode_f32(&mut buf).await.unwrap() {
+            assert_eq!(frames * 2, buf.len());
+            out.extend_from_slice(&buf);
+        }
+        out
+    });
+    assert_eq!(decoded, expected);
+}
+
+#[test]
+fn async_stream_yields_every_block() {
+    use futures_util::StreamExt;
+    let file = crate::test_util::adpcm_cks(1, &[[100, 200], [300, 400], [-500, 600], [0, 7]]);
+    let mut sync = crate::decoder::Decoder::new(Cursor::new(file.clone())).unwrap();
+    sync.set_block_pos(1);
+    let mut expected = Vec::new();
+    let mut buf = Vec::new();
+    while sync.decode_f32(&mut buf).unwrap().is_some() {
+        expected.push(buf.clone());
+    }
+    let blocks = block_on(async {
+        let mut decoder = AsyncDecoder::new(Cursor::new(file.clone())).await.unwrap();
+        decoder.set_block_pos(1).await.unwrap();
+        decoder.into_stream().collect::<Vec<_>>().await
+    });
+    let blocks = blocks.into_iter().collect::<Result<Vec<_>, _>>().unwrap();
+    assert_eq!(blocks.len(), 3);
+    assert!(blocks.iter().all(|b| b.len() == 36));
+    assert_eq!(blocks, expected);
+
+    //a cut off last block is an error once the block count is known.
+    let blocks = block_on(async {
+        let decoder = AsyncDecoder::new(Cursor::new(&file[..file.len() - 10]))
+            .await
+            .unwrap();
+        decoder.into_stream().collect::<Vec<_>>().await
+    });
+    assert_eq!(blocks.len(), 4);
+    assert!(blocks[..3].iter().all(|b| b.is_ok()));
+    assert!(matches!(blocks[3], Err(CksError::InsufficientData)));
+    let end = block_on(async {
+        let mut decoder = AsyncDecoder::new(Cursor::new(file)).await.unwrap();
+        decoder.set_block_pos(u64::MAX).await.unwrap();
+        decoder.decode_f32(&mut Vec::new()).await
+    });
+    assert!(matches!(end, Ok(None)));
+    let not_cks = block_on(AsyncDecoder::new(Cursor::new(vec![0_u8; 64])));
+    assert!(matches!(not_cks, Err(CksError::NotCksFile)));
+}
@@ -1,6 +1,8 @@
 pub mod AudioUtil {
+    use alloc::vec::Vec;
+
     #[inline]
-    pub fn convert_f_to_i32(in_buf: &Vec<u8>, out_buf: &mut Vec<i32>) {
+    pub fn convert_f_to_i32(in_buf: &[u8], out_buf: &mut Vec<i32>) {
         assert_eq!(in_buf.len() % 4, 0);
         if in_buf.len() / 4 != out_buf.len() {
             out_buf.resize(in_buf.len() / 4, 0);
@@ -12,7 +14,7 @@ pub mod AudioUtil {
     }
 
     #[inline]
-    pub fn convert_f_to_i16(in_buf: &Vec<u8>, out_buf: &mut Vec<i16>) {
+    pub fn convert_f_to_i16(in_buf: &[u8], out_buf: &mut Vec<i16>) {
         assert_eq!(in_buf.len() % 4, 0);
         if in_buf.len() / 4 != out_buf.len() {
             out_buf.resize(in_buf.len() / 4, 0);
@@ -24,7 +26,7 @@ pub mod AudioUtil {
     }
 
     #[inline]
-    pub fn convert_i32_to_f(in_buf: &Vec<u8>, out_buf: &mut Vec<f32>) {
+    pub fn convert_i32_to_f(in_buf: &[u8], out_buf: &mut Vec<f32>) {
         assert_eq!(in_buf.len() % 4, 0);
         if in_buf.len() / 4 != out_buf.len() {
             out_buf.resize(in_buf.len() / 4, 0.);
@@ -37,7 +39,7 @@ pub mod AudioUtil {
     }
 
     #[inline]
-    pub fn convert_i32_to_i16(in_buf: &Vec<u8>, out_buf: &mut Vec<i16>) {
+    pub fn convert_i32_to_i16(in_buf: &[u8], out_buf: &mut Vec<i16>) {
         assert_eq!(in_buf.len() % 4, 0);
         if in_buf.len() / 4 != out_buf.len() {
             out_buf.resize(in_buf.len() / 4, 0);
@@ -49,49 +51,53 @@ pub mod AudioUtil {
     }
 
     #[inline]
-    pub fn convert_i16_to_f(in_buf: &Vec<u8>, out_buf: &mut Vec<f32>) {
+    pub fn convert_i16_to_f(in_buf: &[u8], out_buf: &mut Vec<f32>) {
         assert_eq!(in_buf.len() % 2, 0);
         if in_buf.len() / 2 != out_buf.len() {
             out_buf.resize(in_buf.len() / 2, 0.);
         }
         let factor = 1.0_f32 / i16::MAX as f32;
-        for (i, c) in in_buf.rchunks(2).enumerate() {
+        for (i, c) in in_buf.chunks(2).enumerate() {
             let i16_buf = i16::from_le_bytes(c.try_into().unwrap());
             out_buf[i] = i16_buf as f32 * factor;
         }
     }
 
     #[inline]
-    pub fn convert_i16_to_i32(in_buf: &Vec<u8>, out_buf: &mut Vec<i32>) {
+    pub fn convert_i16_to_i32(in_buf: &[u8], out_buf: &mut Vec<i32>) {
         assert_eq!(in_buf.len() % 2, 0);
         if in_buf.len() / 2 != out_buf.len() {
             out_buf.resize(in_buf.len() / 2, 0);
         }
-        for (i, c) in in_buf.rchunks(2).enumerate() {
+        for (i, c) in in_buf.chunks(2).enumerate() {
             let i16_buf = i16::from_le_bytes(c.try_into().unwrap());
             out_buf[i] = (i16_buf as i32) << 9;
         }
     }
 
     #[inline]
-    pub fn convert_i8_f(in_buf: &Vec<u8>, out_buf: &mut Vec<f32>) {
-        assert_eq!(out_buf.len() % 4, 0);
+    pub fn convert_i8_f(in_buf: &[u8], out_buf: &mut Vec<f32>) {
+        if in_buf.len() != out_buf.len() {
+            out_buf.resize(in_buf.len(), 0.);
+        }
         let factor = 1.0_f32 / i8::MAX as f32;
-        for (i, c) in in_buf.iter().rev().enumerate() {
-            out_buf[i] = i8::from_le_bytes(*std::array::from_ref(c)) as f32 * factor;
+        for (i, c) in in_buf.iter().enumerate() {
+            out_buf[i] = i8::from_le_bytes(*core::array::from_ref(c)) as f32 * factor;
         }
     }
 
     #[inline]
-    pub fn convert_i8_to_i32(in_buf: &Vec<u8>, out_buf: &mut Vec<i32>) {
-        assert_eq!(out_buf.len() % 4, 0);
-        for (i, c) in in_buf.iter().rev().enumerate() {
-            out_buf[i] = (i8::from_le_bytes(*std::array::from_ref(c)) as i32) << 17;
+    pub fn convert_i8_to_i32(in_buf: &[u8], out_buf: &mut Vec<i32>) {
+        if in_buf.len() != out_buf.len() {
+            out_buf.resize(in_buf.len(), 0);
+        }
+        for (i, c) in in_buf.iter().enumerate() {
+            out_buf[i] = (i8::from_le_bytes(*core::array::from_ref(c)) as i32) << 17;
         }
     }
 
     #[inline]
-    pub fn convert_f_to_f(in_buf: &Vec<u8>, out_buf: &mut Vec<f32>) {
+    pub fn convert_f_to_f(in_buf: &[u8], out_buf: &mut Vec<f32>) {
         assert_eq!(in_buf.len() % 4, 0);
         if in_buf.len() / 4 != out_buf.len() {
             out_buf.resize(in_buf.len() / 4, 0.);
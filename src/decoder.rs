@@ -1,24 +1,27 @@
-use std::io::{Read, Seek};
+use std::io::{Read, Seek, SeekFrom};
 
 use super::FormatType;
-use crate::decoder_core::core::DecoderCore;
+use crate::audio_util::AudioUtil;
+use crate::decoder_core::block;
 use crate::error::CksError;
+use crate::file_header::{self, HEADER_BYTES};
+use crate::sample::info::SampleInfo;
+use crate::source;
 
-#[derive(Clone, Debug)]
-pub enum DecoderType {
-    Adpcm,
-    Pcmi8,
-    Pcmi16,
-    Pcmf32,
-    Unknown,
-}
+pub use crate::sample::info::DecoderType;
 
+//the seekable std api on top of the same block decoding as every other decoder;
+//reads go through ByteSource, only seeking needs the reader itself.
 pub struct Decoder<R>
 where
     R: Read + Seek,
 {
-    decorder_core: DecoderCore<R>,
-    decoder_type: DecoderType,
+    reader: R,
+    sample_info: SampleInfo,
+    stream_size: u64,
+    position: u64,
+    reader_buf: Vec<u8>,
+    block_buf: Vec<i16>,
 }
 
 impl<R> Decoder<R>
@@ -26,45 +29,125 @@ where
     R: Read + Seek,
 {
     pub fn new(mut reader: R) -> Result<Self, CksError> {
-        let _ = reader.rewind();
-        if !Decoder::is_cks(reader.by_ref()) {
-            return Err(CksError::NotCksFile);
+        let stream_size = reader.seek(SeekFrom::End(0)).or(Err(CksError::Io))?;
+        reader.rewind().or(Err(CksError::Io))?;
+        let mut head = [0_u8; HEADER_BYTES];
+        if source::fill(&mut reader, &mut head)? < HEADER_BYTES {
+            return Err(if head.starts_with(b"ckmk") {
+                CksError::FileRead
+            } else {
+                CksError::NotCksFile
+            });
         }
-        let decorder_core = DecoderCore::new(reader)?;
-        let decoder_type = decorder_core.sample_info.format.clone();
-
+        let sample_info = file_header::parse_header(&head)?;
+        let reader_buf = Vec::with_capacity(sample_info.block_bytes as usize * 2);
         Ok(Self {
-            decorder_core,
-            decoder_type,
+            reader,
+            sample_info,
+            stream_size,
+            position: HEADER_BYTES as u64,
+            reader_buf,
+            block_buf: Vec::new(),
         })
     }
 
+    //Int16 takes one adpcm block and returns amount of samples, Int32 and Float take the given
+    //pcm blocks and return amount of bytes read. None at the end or for a format the buffer
+    //doesn't hold.
     pub fn decode(&mut self, buf: &mut FormatType, blocks: usize) -> Option<u64> {
-        self.decorder_core.decode(buf, blocks as i32)
+        let block_bytes = self.sample_info.block_bytes as usize;
+        let channels = self.sample_info.channels as usize;
+        let format = self.sample_info.format.clone();
+        match (buf, &format) {
+            (FormatType::Int16(v), DecoderType::Adpcm) => {
+                let bytes = self.read(block_bytes).ok()?;
+                if bytes < block_bytes {
+                    return None;
+                }
+                let samples = block::adpcm_block_frames(block_bytes / channels.max(1)) * channels;
+                if v.len() < samples {
+                    v.resize(samples, 0);
+                }
+                block::decode_adpcm_block(channels, &self.reader_buf[..bytes], v).ok()?;
+                Some(samples as u64)
+            }
+            (FormatType::Int32(v), format) => {
+                let bytes = self.read(blocks.saturating_mul(block_bytes)).ok()?;
+                let reader_buf = &self.reader_buf[..bytes];
+                match format {
+                    DecoderType::Pcmi8 => AudioUtil::convert_i8_to_i32(reader_buf, v),
+                    DecoderType::Pcmi16 => AudioUtil::convert_i16_to_i32(reader_buf, v),
+                    DecoderType::Pcmf32 => AudioUtil::convert_f_to_i32(reader_buf, v),
+                    _ => return None,
+                }
+                (bytes > 0).then_some(bytes as u64)
+            }
+            (FormatType::Float(v), format) => {
+                let bytes = self.read(blocks.saturating_mul(block_bytes)).ok()?;
+                let reader_buf = &self.reader_buf[..bytes];
+                match format {
+                    DecoderType::Pcmi8 => AudioUtil::convert_i8_f(reader_buf, v),
+                    DecoderType::Pcmi16 => AudioUtil::convert_i16_to_f(reader_buf, v),
+                    DecoderType::Pcmf32 => AudioUtil::convert_f_to_f(reader_buf, v),
+                    _ => return None,
+                }
+                (bytes > 0).then_some(bytes as u64)
+            }
+            _ => None,
+        }
     }
 
     pub fn next(&mut self, buf: &mut FormatType) -> Option<u64> {
-        self.decorder_core.decode(buf, 1)
+        self.decode(buf, 1)
+    }
+
+    //decodes the next block into interleaved f32 whatever the stored format is.
+    //returns amount of frames decoded, None at the end of the stream.
+    pub fn decode_f32(&mut self, buf: &mut Vec<f32>) -> Result<Option<usize>, CksError> {
+        let block_bytes = self.sample_info.block_bytes as usize;
+        let bytes = self.read(block_bytes)?;
+        if bytes == 0 {
+            return Ok(None);
+        }
+        block::decode_blocks_f32(
+            &self.sample_info.format,
+            self.sample_info.channels as usize,
+            block_bytes,
+            &self.reader_buf[..bytes],
+            buf,
+            &mut self.block_buf,
+        )
+        .map(Some)
+    }
+
+    //block starts with 0.
+    pub fn set_block_pos(&mut self, block: i32) {
+        let pos = (block.max(0) as u64)
+            .saturating_mul(self.sample_info.block_bytes as u64)
+            .saturating_add(HEADER_BYTES as u64);
+        if let Ok(pos) = self.reader.seek(SeekFrom::Start(pos)) {
+            self.position = pos;
+        }
     }
 
     pub fn into_inner(self) -> R {
-        self.decorder_core.into_inner()
+        self.reader
     }
 
-    pub fn sample_info(&self) -> crate::sample::info::SampleInfo {
-        self.decorder_core.sample_info.clone()
+    pub fn sample_info(&self) -> SampleInfo {
+        self.sample_info.clone()
     }
 
-    fn is_cks(mut reader: R) -> bool {
-        let cks_marker = [b'c', b'k', b'm', b'k'];
-        let mut buf = [0u8; 4];
-        let current_pos = reader.stream_position().unwrap();
-        if current_pos != 0 {
-            let _ = reader.seek(std::io::SeekFrom::Start(0));
+    //reads up to the given bytes of block data into reader_buf, returns amount read.
+    fn read(&mut self, bytes: usize) -> Result<usize, CksError> {
+        let left = self.stream_size.saturating_sub(self.position);
+        let bytes = std::cmp::min(bytes as u64, left) as usize;
+        if self.reader_buf.len() < bytes {
+            self.reader_buf.resize(bytes, 0);
         }
-        let _ = reader.read_exact(&mut buf);
-        let _ = reader.seek(std::io::SeekFrom::Start(current_pos));
-        cks_marker == buf
+        let read = source::fill(&mut self.reader, &mut self.reader_buf[..bytes])?;
+        self.position += read as u64;
+        Ok(read)
     }
 }
 
@@ -98,6 +181,73 @@ fn t() {
     }
 }
 
+#[test]
+fn decode_keeps_pcm_samples_in_order() {
+    let samples = [1000_i16, 2000, 3000, 4000];
+    let file = crate::test_util::pcm16_cks(1, 8000, &samples, (0, 0, 0));
+    let mut dec = Decoder::new(std::io::Cursor::new(file)).unwrap();
+    let mut buf = FormatType::new_float32();
+    assert_eq!(dec.decode(&mut buf, 4), Some(8));
+    let expected = samples.map(|s| s as f32 / i16::MAX as f32).to_vec();
+    assert_eq!(buf, FormatType::Float(expected));
+}
+
+#[test]
+fn decode_converts_only_the_bytes_read() {
+    let info = crate::sample::info::SampleInfo {
+        format: DecoderType::Pcmi8,
+        channels: 1,
+        sample_rate: 8000,
+        blocks: 3,
+        block_bytes: 1,
+        block_frames: 1,
+        volume: u16::MAX,
+        pan: 0,
+        loop_start: 0,
+        loop_end: 0,
+        loop_count: 0,
+    };
+    let file = crate::test_util::cks_file(&info, &[10, 20, 30]);
+    let mut dec = Decoder::new(std::io::Cursor::new(file)).unwrap();
+    let mut buf = FormatType::new_float32();
+    let factor = 1.0 / i8::MAX as f32;
+    assert_eq!(dec.decode(&mut buf, 2), Some(2));
+    assert_eq!(buf, FormatType::Float(vec![10.0 * factor, 20.0 * factor]));
+    //the last read is shorter than the one before.
+    assert_eq!(dec.decode(&mut buf, 2), Some(1));
+    assert_eq!(buf, FormatType::Float(vec![30.0 * factor]));
+}
+
+#[test]
+fn decode_reads_mono_adpcm_block_by_block() {
+    let file = crate::test_util::adpcm_cks(1, &[[100, 0], [300, 0], [500, 0]]);
+    let mut dec = Decoder::new(std::io::Cursor::new(file)).unwrap();
+    let mut buf = FormatType::new_int16();
+    let mut starts = Vec::new();
+    while dec.decode(&mut buf, 1).is_some() {
+        if let FormatType::Int16(v) = &buf {
+            starts.push(v[0]);
+        }
+    }
+    assert_eq!(starts, [100, 300, 500]);
+}
+
+#[test]
+fn decode_f32_reports_corrupt_blocks() {
+    let mut file = crate::test_util::adpcm_cks(1, &[[100, 0], [300, 0]]);
+    file[HEADER_BYTES + 24] = 9;
+    let mut dec = Decoder::new(std::io::Cursor::new(file)).unwrap();
+    let mut buf = Vec::new();
+    assert_eq!(dec.decode_f32(&mut buf).unwrap(), Some(36));
+    assert!(matches!(
+        dec.decode_f32(&mut buf),
+        Err(CksError::InvalidBlock)
+    ));
+    assert_eq!(dec.decode_f32(&mut buf).unwrap(), None);
+    dec.set_block_pos(1);
+    assert_eq!(dec.next(&mut FormatType::new_int16()), None);
+}
+
 #[test]
 fn v() {
     let buf0 = vec![0_i16; 72];
@@ -1,11 +1,7 @@
-use crate::{decoder_core::core::DecoderCore, error::AdpcmError};
-use std::io::{Read, Seek, SeekFrom};
+use crate::error::AdpcmError;
 
 #[derive(Debug)]
-pub(crate) struct AdpcmCore {
-    bytes_per_block: usize,
-    buf: Vec<u8>,
-}
+pub(crate) struct AdpcmCore;
 
 pub(crate) static BYTES_PER_BLOCK_DEFAULT: usize = 24;
 static FIXED_POINT_COEF_BASE: i32 = 256;
@@ -29,55 +25,15 @@ static ADAPTION_TABLE: [i16; 16] = [
 //static mut TEST_I: usize = 0;
 
 impl AdpcmCore {
-    pub fn decode<R: Read + Seek>(
-        decoder_core: &mut DecoderCore<R>,
-        output_buf: &mut [i16],
-    ) -> Option<usize> {
-        let mut decoded_bytes = 0;
-        if decoder_core.adpcm_core.is_none() {
-            decoder_core.adpcm_core = Some(Self {
-                bytes_per_block: BYTES_PER_BLOCK_DEFAULT,
-                buf: Vec::with_capacity(BYTES_PER_BLOCK_DEFAULT * 2),
-            });
-        }
-        let block_read = Self::read(decoder_core, 1);
-        assert!(block_read == Some(1) || block_read == None);
-
-        let buf_read = &mut decoder_core.adpcm_core.as_mut().unwrap().buf;
-        if block_read == None {
-            return None;
-        } else {
-            decoded_bytes += Self::dec_core(
-                &buf_read[..BYTES_PER_BLOCK_DEFAULT],
-                BYTES_PER_BLOCK_DEFAULT,
-                output_buf,
-                decoder_core.sample_info.channels,
-            )
-            .unwrap();
-            if decoder_core.sample_info.channels == 2 {
-                decoded_bytes += Self::dec_core(
-                    &buf_read[BYTES_PER_BLOCK_DEFAULT..],
-                    BYTES_PER_BLOCK_DEFAULT,
-                    &mut output_buf[1..],
-                    decoder_core.sample_info.channels,
-                )
-                .unwrap();
-            }
-        }
-        Some(decoded_bytes)
-    }
-
-    fn dec_core(
+    pub(crate) fn dec_core(
         in_buf: &[u8],
         input_byte: usize,
         out_buf: &mut [i16],
         output_stride: u8,
     ) -> Result<usize, AdpcmError> {
         if !(output_stride == 1 || output_stride == 2) {
-            eprintln!("output stride = {}", output_stride);
             return Err(AdpcmError::InvalidStride);
         } else if input_byte < 7 {
-            eprintln!("input bytes = {}", input_byte);
             return Err(AdpcmError::NoEnoughInputBytes);
         }
         let output_stride = output_stride as usize;
@@ -101,8 +57,8 @@ impl AdpcmCore {
         out_buf[output_index] = samp1;
         output_index += output_stride;
 
-        let coef1 = COEFFS[predictor][0];
-        let coef2 = COEFFS[predictor][1];
+        //the predictor comes straight from the file, a corrupt block can point past the table.
+        let [coef1, coef2] = *COEFFS.get(predictor).ok_or(AdpcmError::InvalidPredictor)?;
 
         while input_index < input_end {
             for nybble in 0..2 {
@@ -135,38 +91,6 @@ impl AdpcmCore {
         assert_eq!(output_samples, 2 * input_byte - 12);
         Ok(output_samples)
     }
-
-    fn read<R: Read + Seek>(decoder_core: &mut DecoderCore<R>, blocks: usize) -> Option<u64> {
-        let core = decoder_core.adpcm_core.as_mut().unwrap();
-        let mut bytes = core.buf.len();
-        let buf = &mut core.buf;
-        if bytes != BYTES_PER_BLOCK_DEFAULT * 2 * blocks {
-            buf.resize(BYTES_PER_BLOCK_DEFAULT * 2 * blocks, 0);
-            bytes = buf.len();
-        }
-        let bytes_to_end = std::cmp::max(
-            decoder_core.stream_size - decoder_core.reader.stream_position().unwrap(),
-            0,
-        );
-        let bytes_to_read = std::cmp::min(bytes as u64, bytes_to_end) as usize;
-        if bytes_to_read > 0 {
-            'check_byte: while let Ok(res) = decoder_core.reader.read(&mut buf[0..bytes_to_read]) {
-                if res != bytes_to_read {
-                    decoder_core
-                        .reader
-                        .seek(SeekFrom::Current(-(res as i64)))
-                        .unwrap();
-                } else {
-                    break 'check_byte;
-                }
-            }
-        } else {
-            eprintln!("NONE!");
-            return None;
-        }
-
-        Some((bytes_to_read / (BYTES_PER_BLOCK_DEFAULT * 2)) as _)
-    }
 }
 
 #[inline]
@@ -0,0 +1,105 @@
+use crate::{
+    audio_util::AudioUtil,
+    decoder_core::adpcm::AdpcmCore,
+    error::{AdpcmError, CksError},
+    sample::info::DecoderType,
+};
+use alloc::vec::Vec;
+
+//decodes whole blocks held in memory into interleaved f32 without touching a reader.
+//returns amount of frames decoded. block_bytes matters for adpcm only, pcm blocks are plain frames.
+//block_buf is scratch space for one adpcm block, kept by the caller so it isn't allocated per call.
+pub(crate) fn decode_blocks_f32(
+    format: &DecoderType,
+    channels: usize,
+    block_bytes: usize,
+    bytes: &[u8],
+    out: &mut Vec<f32>,
+    block_buf: &mut Vec<i16>,
+) -> Result<usize, CksError> {
+    let channels = channels.max(1);
+    match format {
+        DecoderType::Adpcm => {
+            let bytes_per_channel = block_bytes / channels;
+            if bytes_per_channel < 7 {
+                return Err(CksError::InsufficientData);
+            }
+            block_buf.resize(adpcm_block_frames(bytes_per_channel) * channels, 0);
+            let factor = 1.0_f32 / i16::MAX as f32;
+            out.clear();
+            for block in bytes.chunks_exact(block_bytes) {
+                decode_adpcm_block(channels, block, block_buf)?;
+                out.extend(block_buf.iter().map(|s| *s as f32 * factor));
+            }
+        }
+        DecoderType::Pcmi8 => AudioUtil::convert_i8_f(bytes, out),
+        DecoderType::Pcmi16 => AudioUtil::convert_i16_to_f(&bytes[..bytes.len() / 2 * 2], out),
+        DecoderType::Pcmf32 => AudioUtil::convert_f_to_f(&bytes[..bytes.len() / 4 * 4], out),
+        DecoderType::Unknown => return Err(CksError::UnknownFormat),
+    }
+    Ok(out.len() / channels)
+}
+
+//decodes one adpcm block into interleaved i16, returns amount of frames decoded.
+//out has to hold the frames of the whole block.
+pub(crate) fn decode_adpcm_block(
+    channels: usize,
+    block: &[u8],
+    out: &mut [i16],
+) -> Result<usize, CksError> {
+    let channels = channels.max(1);
+    let bytes_per_channel = block.len() / channels;
+    if bytes_per_channel < 7 {
+        return Err(CksError::InsufficientData);
+    }
+    for c in 0..channels {
+        AdpcmCore::dec_core(
+            &block[c * bytes_per_channel..(c + 1) * bytes_per_channel],
+            bytes_per_channel,
+            &mut out[c..],
+            channels as u8,
+        )
+        .map_err(|e| match e {
+            AdpcmError::InvalidStride => CksError::UnsupportedDecType,
+            AdpcmError::NoEnoughInputBytes => CksError::InsufficientData,
+            AdpcmError::InvalidPredictor => CksError::InvalidBlock,
+        })?;
+    }
+    Ok(adpcm_block_frames(bytes_per_channel))
+}
+
+//two raw samples up front, then two nibbles per byte.
+pub(crate) fn adpcm_block_frames(bytes_per_channel: usize) -> usize {
+    (2 * bytes_per_channel).saturating_sub(12)
+}
+
+#[test]
+fn decode_blocks_rejects_a_corrupt_predictor() {
+    let mut block = [0_u8; 24];
+    block[0] = 7;
+    let mut out = Vec::new();
+    assert!(matches!(
+        decode_blocks_f32(
+            &DecoderType::Adpcm,
+            1,
+            24,
+            &block,
+            &mut out,
+            &mut Vec::new()
+        ),
+        Err(CksError::InvalidBlock)
+    ));
+    block[0] = 6;
+    assert_eq!(
+        decode_blocks_f32(
+            &DecoderType::Adpcm,
+            1,
+            24,
+            &block,
+            &mut out,
+            &mut Vec::new()
+        )
+        .unwrap(),
+        36
+    );
+}
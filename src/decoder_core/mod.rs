@@ -1,2 +1,2 @@
 pub(crate) mod adpcm;
-pub mod core;
+pub(crate) mod block;
@@ -0,0 +1,294 @@
+//RBJ cookbook biquads with smoothed parameters.
+use super::Effect;
+
+//coefficients are recomputed at most once per this many frames while parameters move.
+static SMOOTH_BLOCK: usize = 16;
+static SMOOTH_MS: f32 = 20.0;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum FilterType {
+    LowPass,
+    HighPass,
+    BandPass,
+    Notch,
+    Peaking,
+    LowShelf,
+    HighShelf,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Params {
+    freq: f32,
+    q: f32,
+    gain_db: f32,
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+struct Coeffs {
+    b0: f32,
+    b1: f32,
+    b2: f32,
+    a1: f32,
+    a2: f32,
+}
+
+pub struct Biquad {
+    kind: FilterType,
+    target: Params,
+    current: Params,
+    coeffs: Coeffs,
+    coeffs_rate: u32,
+    smoothing: bool,
+    //transposed direct form II state per channel.
+    state: Vec<[f32; 2]>,
+    block_pos: usize,
+}
+
+impl Biquad {
+    pub fn new(kind: FilterType, freq: f32, q: f32, gain_db: f32) -> Self {
+        let params = Params {
+            freq: freq.max(1.0),
+            q: q.max(0.01),
+            gain_db,
+        };
+        Self {
+            kind,
+            target: params,
+            current: params,
+            coeffs: Coeffs::default(),
+            coeffs_rate: 0,
+            smoothing: false,
+            state: Vec::new(),
+            block_pos: 0,
+        }
+    }
+
+    pub fn low_pass(freq: f32, q: f32) -> Self {
+        Self::new(FilterType::LowPass, freq, q, 0.0)
+    }
+
+    pub fn high_pass(freq: f32, q: f32) -> Self {
+        Self::new(FilterType::HighPass, freq, q, 0.0)
+    }
+
+    pub fn band_pass(freq: f32, q: f32) -> Self {
+        Self::new(FilterType::BandPass, freq, q, 0.0)
+    }
+
+    pub fn notch(freq: f32, q: f32) -> Self {
+        Self::new(FilterType::Notch, freq, q, 0.0)
+    }
+
+    pub fn peaking(freq: f32, q: f32, gain_db: f32) -> Self {
+        Self::new(FilterType::Peaking, freq, q, gain_db)
+    }
+
+    //q of the shelves is the shelf slope, 0.707 gives the usual steepest monotonic shelf.
+    pub fn low_shelf(freq: f32, q: f32, gain_db: f32) -> Self {
+        Self::new(FilterType::LowShelf, freq, q, gain_db)
+    }
+
+    pub fn high_shelf(freq: f32, q: f32, gain_db: f32) -> Self {
+        Self::new(FilterType::HighShelf, freq, q, gain_db)
+    }
+
+    pub fn filter_type(&self) -> FilterType {
+        self.kind
+    }
+
+    pub fn frequency(&self) -> f32 {
+        self.target.freq
+    }
+
+    pub fn q(&self) -> f32 {
+        self.target.q
+    }
+
+    pub fn gain_db(&self) -> f32 {
+        self.target.gain_db
+    }
+
+    //changes are glided over about SMOOTH_MS instead of jumping.
+    pub fn set_frequency(&mut self, freq: f32) {
+        self.target.freq = freq.max(1.0);
+        self.smoothing = true;
+    }
+
+    pub fn set_q(&mut self, q: f32) {
+        self.target.q = q.max(0.01);
+        self.smoothing = true;
+    }
+
+    pub fn set_gain_db(&mut self, gain_db: f32) {
+        self.target.gain_db = gain_db;
+        self.smoothing = true;
+    }
+
+    pub fn set_filter_type(&mut self, kind: FilterType) {
+        self.kind = kind;
+        self.coeffs_rate = 0;
+    }
+
+    fn smooth(&mut self, sample_rate: u32) {
+        let alpha = 1.0 - (-(SMOOTH_BLOCK as f32) / (SMOOTH_MS * 0.001 * sample_rate as f32)).exp();
+        let (cur, tgt) = (&mut self.current, &self.target);
+        cur.freq = (cur.freq.ln() + (tgt.freq.ln() - cur.freq.ln()) * alpha).exp();
+        cur.q += (tgt.q - cur.q) * alpha;
+        cur.gain_db += (tgt.gain_db - cur.gain_db) * alpha;
+        let close = (cur.freq - tgt.freq).abs() < 0.01
+            && (cur.q - tgt.q).abs() < 1e-4
+            && (cur.gain_db - tgt.gain_db).abs() < 1e-3;
+        if close {
+            *cur = *tgt;
+            self.smoothing = false;
+        }
+        self.coeffs = coeffs(self.kind, *cur, sample_rate);
+    }
+}
+
+impl Effect for Biquad {
+    fn process(&mut self, buf: &mut [f32], channels: usize, sample_rate: u32) {
+        let channels = channels.max(1);
+        if self.state.len() != channels {
+            self.state = vec![[0.0; 2]; channels];
+        }
+        if self.coeffs_rate != sample_rate {
+            self.coeffs_rate = sample_rate;
+            self.coeffs = coeffs(self.kind, self.current, sample_rate);
+        }
+        for frame in buf.chunks_exact_mut(channels) {
+            if self.smoothing {
+                if self.block_pos == 0 {
+                    self.smooth(sample_rate);
+                }
+                self.block_pos = (self.block_pos + 1) % SMOOTH_BLOCK;
+            }
+            let c = self.coeffs;
+            for (s, z) in frame.iter_mut().zip(self.state.iter_mut()) {
+                let x = *s;
+                let y = c.b0 * x + z[0];
+                z[0] = c.b1 * x - c.a1 * y + z[1];
+                z[1] = c.b2 * x - c.a2 * y;
+                *s = y;
+            }
+        }
+    }
+
+    fn reset(&mut self) {
+        self.state.iter_mut().for_each(|z| *z = [0.0; 2]);
+        self.current = self.target;
+        self.smoothing = false;
+        self.coeffs_rate = 0;
+    }
+}
+
+fn coeffs(kind: FilterType, p: Params, sample_rate: u32) -> Coeffs {
+    let nyquist = sample_rate as f32 * 0.5;
+    let freq = p.freq.min(nyquist * 0.99);
+    let w0 = 2.0 * std::f32::consts::PI * freq / sample_rate.max(1) as f32;
+    let (sin, cos) = w0.sin_cos();
+    let alpha = sin / (2.0 * p.q);
+    let a = 10.0_f32.powf(p.gain_db / 40.0);
+    let (b0, b1, b2, a0, a1, a2) = match kind {
+        FilterType::LowPass => (
+            (1.0 - cos) / 2.0,
+            1.0 - cos,
+            (1.0 - cos) / 2.0,
+            1.0 + alpha,
+            -2.0 * cos,
+            1.0 - alpha,
+        ),
+        FilterType::HighPass => (
+            (1.0 + cos) / 2.0,
+            -(1.0 + cos),
+            (1.0 + cos) / 2.0,
+            1.0 + alpha,
+            -2.0 * cos,
+            1.0 - alpha,
+        ),
+        FilterType::BandPass => (alpha, 0.0, -alpha, 1.0 + alpha, -2.0 * cos, 1.0 - alpha),
+        FilterType::Notch => (1.0, -2.0 * cos, 1.0, 1.0 + alpha, -2.0 * cos, 1.0 - alpha),
+        FilterType::Peaking => (
+            1.0 + alpha * a,
+            -2.0 * cos,
+            1.0 - alpha * a,
+            1.0 + alpha / a,
+            -2.0 * cos,
+            1.0 - alpha / a,
+        ),
+        FilterType::LowShelf => {
+            let k = 2.0 * a.sqrt() * alpha;
+            (
+                a * ((a + 1.0) - (a - 1.0) * cos + k),
+                2.0 * a * ((a - 1.0) - (a + 1.0) * cos),
+                a * ((a + 1.0) - (a - 1.0) * cos - k),
+                (a + 1.0) + (a - 1.0) * cos + k,
+                -2.0 * ((a - 1.0) + (a + 1.0) * cos),
+                (a + 1.0) + (a - 1.0) * cos - k,
+            )
+        }
+        FilterType::HighShelf => {
+            let k = 2.0 * a.sqrt() * alpha;
+            (
+                a * ((a + 1.0) + (a - 1.0) * cos + k),
+                -2.0 * a * ((a - 1.0) + (a + 1.0) * cos),
+                a * ((a + 1.0) + (a - 1.0) * cos - k),
+                (a + 1.0) - (a - 1.0) * cos + k,
+                2.0 * ((a - 1.0) - (a + 1.0) * cos),
+                (a + 1.0) - (a - 1.0) * cos - k,
+            )
+        }
+    };
+    Coeffs {
+        b0: b0 / a0,
+        b1: b1 / a0,
+        b2: b2 / a0,
+        a1: a1 / a0,
+        a2: a2 / a0,
+    }
+}
+
+#[cfg(test)]
+fn gain_at(mut filter: Biquad, freq: f32) -> f32 {
+    let mut buf = super::sine(freq, 48000, 9600, 2);
+    filter.process(&mut buf, 2, 48000);
+    super::rms(&buf[4800..]) / super::rms(&super::sine(freq, 48000, 4800, 2))
+}
+
+#[test]
+fn biquad_responses() {
+    assert!(gain_at(Biquad::low_pass(1000.0, 0.707), 100.0) > 0.95);
+    assert!(gain_at(Biquad::low_pass(1000.0, 0.707), 10000.0) < 0.02);
+    assert!(gain_at(Biquad::high_pass(1000.0, 0.707), 100.0) < 0.02);
+    assert!(gain_at(Biquad::high_pass(1000.0, 0.707), 10000.0) > 0.95);
+    assert!(gain_at(Biquad::band_pass(1000.0, 2.0), 1000.0) > 0.95);
+    assert!(gain_at(Biquad::band_pass(1000.0, 2.0), 10000.0) < 0.1);
+    assert!(gain_at(Biquad::notch(1000.0, 2.0), 1000.0) < 0.01);
+    assert!((gain_at(Biquad::peaking(1000.0, 1.0, 6.0), 1000.0) - 1.995).abs() < 0.05);
+    assert!((gain_at(Biquad::low_shelf(500.0, 0.707, -12.0), 50.0) - 0.251).abs() < 0.02);
+    assert!((gain_at(Biquad::high_shelf(2000.0, 0.707, -12.0), 15000.0) - 0.251).abs() < 0.02);
+}
+
+#[test]
+fn biquad_channels_are_independent() {
+    let mut buf = super::sine(10000.0, 48000, 4800, 3);
+    for frame in buf.chunks_exact_mut(3) {
+        frame[1] = 0.0;
+    }
+    Biquad::low_pass(1000.0, 0.707).process(&mut buf, 3, 48000);
+    assert!(buf.chunks_exact(3).all(|f| f[1] == 0.0));
+}
+
+#[test]
+fn biquad_glides_to_new_frequency() {
+    let mut filter = Biquad::low_pass(200.0, 0.707);
+    let mut buf = super::sine(2000.0, 48000, 4800, 1);
+    filter.process(&mut buf, 1, 48000);
+    filter.set_frequency(8000.0);
+    let mut buf = super::sine(2000.0, 48000, 9600, 1);
+    filter.process(&mut buf, 1, 48000);
+    //still mostly closed right after the change, open once the glide settled.
+    assert!(super::rms(&buf[..48]) < 0.3);
+    assert!(super::rms(&buf[4800..]) > 0.65);
+    assert_eq!(filter.frequency(), 8000.0);
+}
@@ -0,0 +1,83 @@
+//lowers bit depth and holds samples to fake a lower sample rate.
+use super::Effect;
+
+pub struct BitCrusher {
+    bits: u32,
+    hold_ms: f32,
+    held: Vec<f32>,
+    hold_pos: f64,
+}
+
+impl BitCrusher {
+    pub fn new(bits: u32, hold_ms: f32) -> Self {
+        let mut crusher = Self {
+            bits: 16,
+            hold_ms: 0.0,
+            held: Vec::new(),
+            hold_pos: 0.0,
+        };
+        crusher.set_bits(bits);
+        crusher.set_hold_ms(hold_ms);
+        crusher
+    }
+
+    pub fn bits(&self) -> u32 {
+        self.bits
+    }
+
+    //1..=24, 24 leaves the samples untouched.
+    pub fn set_bits(&mut self, bits: u32) {
+        self.bits = bits.clamp(1, 24);
+    }
+
+    pub fn hold_ms(&self) -> f32 {
+        self.hold_ms
+    }
+
+    //each sample is repeated for this long, 0 turns holding off.
+    pub fn set_hold_ms(&mut self, hold_ms: f32) {
+        self.hold_ms = hold_ms.max(0.0);
+    }
+}
+
+impl Effect for BitCrusher {
+    fn process(&mut self, buf: &mut [f32], channels: usize, sample_rate: u32) {
+        let channels = channels.max(1);
+        if self.held.len() != channels {
+            self.held = vec![0.0; channels];
+            self.hold_pos = 0.0;
+        }
+        let hold_frames = (self.hold_ms as f64 * 0.001 * sample_rate as f64).max(1.0);
+        let steps = (1_u32 << (self.bits - 1)) as f32;
+        for frame in buf.chunks_exact_mut(channels) {
+            if self.hold_pos <= 0.0 {
+                self.hold_pos += hold_frames;
+                for (h, s) in self.held.iter_mut().zip(frame.iter()) {
+                    *h = if self.bits >= 24 {
+                        *s
+                    } else {
+                        (*s * steps).round() / steps
+                    };
+                }
+            }
+            self.hold_pos -= 1.0;
+            frame.copy_from_slice(&self.held);
+        }
+    }
+
+    fn reset(&mut self) {
+        self.held.iter_mut().for_each(|h| *h = 0.0);
+        self.hold_pos = 0.0;
+    }
+}
+
+#[test]
+fn bit_crusher_quantizes_and_holds() {
+    let mut buf = (0..16).map(|i| i as f32 / 16.0).collect::<Vec<_>>();
+    //2 bits leaves the steps -1, -0.5, 0, 0.5, 1, held for 4 frames at 1khz.
+    BitCrusher::new(2, 4.0).process(&mut buf, 1, 1000);
+    let expected = [
+        0.0, 0.0, 0.0, 0.0, 0.5, 0.5, 0.5, 0.5, 0.5, 0.5, 0.5, 0.5, 1.0, 1.0, 1.0, 1.0,
+    ];
+    assert_eq!(buf, expected);
+}
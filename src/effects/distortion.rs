@@ -0,0 +1,97 @@
+//tanh waveshaper. the offset adds even harmonics, and the dc that comes with them is taken out
+//by a one-pole high-pass after the shaper.
+use super::Effect;
+
+pub struct Distortion {
+    drive: f32,
+    offset: f32,
+    wet: f32,
+    //last input and output of the dc blocker, per channel.
+    dc: Vec<[f32; 2]>,
+}
+
+//corner of the dc blocker, well below anything audible.
+static DC_CUTOFF_HZ: f32 = 10.0;
+
+impl Distortion {
+    pub fn new(drive: f32) -> Self {
+        Self {
+            drive: drive.max(1.0),
+            offset: 0.0,
+            wet: 1.0,
+            dc: Vec::new(),
+        }
+    }
+
+    pub fn drive(&self) -> f32 {
+        self.drive
+    }
+
+    //input gain into the shaper, 1 is barely coloured.
+    pub fn set_drive(&mut self, drive: f32) {
+        self.drive = drive.max(1.0);
+    }
+
+    pub fn offset(&self) -> f32 {
+        self.offset
+    }
+
+    pub fn set_offset(&mut self, offset: f32) {
+        self.offset = offset.clamp(-1.0, 1.0);
+    }
+
+    pub fn wet(&self) -> f32 {
+        self.wet
+    }
+
+    pub fn set_wet(&mut self, wet: f32) {
+        self.wet = wet.clamp(0.0, 1.0);
+    }
+}
+
+impl Effect for Distortion {
+    fn process(&mut self, buf: &mut [f32], channels: usize, sample_rate: u32) {
+        let channels = channels.max(1);
+        self.dc.resize(channels, [0.0; 2]);
+        let pole = (-2.0 * std::f32::consts::PI * DC_CUTOFF_HZ / sample_rate.max(1) as f32).exp();
+        let bias = self.offset.tanh();
+        for frame in buf.chunks_exact_mut(channels) {
+            for (s, dc) in frame.iter_mut().zip(self.dc.iter_mut()) {
+                let shaped = (*s * self.drive + self.offset).tanh() - bias;
+                let blocked = shaped - dc[0] + pole * dc[1];
+                *dc = [shaped, blocked];
+                *s += (blocked - *s) * self.wet;
+            }
+        }
+    }
+
+    fn reset(&mut self) {
+        self.dc.iter_mut().for_each(|dc| *dc = [0.0; 2]);
+    }
+}
+
+#[test]
+fn distortion_clips_softly() {
+    let mut buf = super::sine(100.0, 8000, 800, 1);
+    let mut dist = Distortion::new(20.0);
+    dist.set_offset(0.3);
+    dist.process(&mut buf, 1, 8000);
+    //the dc blocker needs a few periods to settle.
+    let buf = &buf[400..];
+    let peak = buf.iter().fold(0.0_f32, |m, s| m.max(s.abs()));
+    assert!(peak < 1.3 && peak > 0.9);
+    //squarer wave, so more energy than the sine.
+    assert!(super::rms(buf) > 0.85);
+}
+
+#[test]
+fn distortion_leaves_no_dc() {
+    let mut buf = super::sine(100.0, 8000, 16000, 2);
+    let mut dist = Distortion::new(8.0);
+    dist.set_offset(0.5);
+    dist.process(&mut buf, 2, 8000);
+    //skip the first second while the blocker settles.
+    let tail = &buf[16000..];
+    let mean = tail.iter().sum::<f32>() / tail.len() as f32;
+    assert!(mean.abs() < 1e-3, "dc {}", mean);
+}
@@ -0,0 +1,308 @@
+//gain control for summed or decoded streams, meant to sit right before integer conversion.
+use std::collections::VecDeque;
+
+use super::Effect;
+
+fn db_to_gain(db: f32) -> f32 {
+    10.0_f32.powf(db / 20.0)
+}
+
+fn gain_to_db(gain: f32) -> f32 {
+    20.0 * gain.max(1e-9).log10()
+}
+
+//one-pole coefficient reaching ~63% of a step after `ms`.
+fn time_coeff(ms: f32, sample_rate: u32) -> f32 {
+    let frames = ms * 0.001 * sample_rate as f32;
+    if frames <= 0.0 {
+        0.0
+    } else {
+        (-1.0 / frames).exp()
+    }
+}
+
+//look-ahead peak limiter. the output is delayed by latency_frames and never exceeds the ceiling.
+pub struct Limiter {
+    ceiling: f32,
+    lookahead_ms: f32,
+    release_ms: f32,
+    sample_rate: u32,
+    channels: usize,
+    window: usize,
+    //delayed input, latency frames long.
+    delay: VecDeque<f32>,
+    //(frame, gain) candidates for the minimum over the window.
+    minimum: VecDeque<(u64, f32)>,
+    envelope: f32,
+    //gains averaged over the window so the reduction fades in instead of stepping.
+    average: VecDeque<f32>,
+    average_sum: f64,
+    frame: u64,
+}
+
+impl Limiter {
+    pub fn new(ceiling_db: f32, lookahead_ms: f32, release_ms: f32) -> Self {
+        Self {
+            ceiling: db_to_gain(ceiling_db.min(0.0)),
+            lookahead_ms: lookahead_ms.max(0.0),
+            release_ms: release_ms.max(0.0),
+            sample_rate: 0,
+            channels: 0,
+            window: 1,
+            delay: VecDeque::new(),
+            minimum: VecDeque::new(),
+            envelope: 1.0,
+            average: VecDeque::new(),
+            average_sum: 0.0,
+            frame: 0,
+        }
+    }
+
+    pub fn ceiling_db(&self) -> f32 {
+        gain_to_db(self.ceiling)
+    }
+
+    pub fn set_ceiling_db(&mut self, ceiling_db: f32) {
+        self.ceiling = db_to_gain(ceiling_db.min(0.0));
+    }
+
+    pub fn release_ms(&self) -> f32 {
+        self.release_ms
+    }
+
+    pub fn set_release_ms(&mut self, release_ms: f32) {
+        self.release_ms = release_ms.max(0.0);
+    }
+
+    pub fn lookahead_ms(&self) -> f32 {
+        self.lookahead_ms
+    }
+
+    //changing the look-ahead restarts the limiter.
+    pub fn set_lookahead_ms(&mut self, lookahead_ms: f32) {
+        self.lookahead_ms = lookahead_ms.max(0.0);
+        self.sample_rate = 0;
+    }
+
+    //frames the output lags behind the input.
+    pub fn latency_frames(&self) -> usize {
+        self.window - 1
+    }
+
+    fn prepare(&mut self, channels: usize, sample_rate: u32) {
+        if self.channels == channels && self.sample_rate == sample_rate {
+            return;
+        }
+        self.channels = channels;
+        self.sample_rate = sample_rate;
+        self.window = ((self.lookahead_ms * 0.001 * sample_rate as f32).round() as usize).max(1);
+        self.reset();
+    }
+}
+
+impl Effect for Limiter {
+    fn process(&mut self, buf: &mut [f32], channels: usize, sample_rate: u32) {
+        let channels = channels.max(1);
+        self.prepare(channels, sample_rate);
+        let release = time_coeff(self.release_ms, sample_rate);
+        let window = self.window;
+        for frame in buf.chunks_exact_mut(channels) {
+            let peak = frame.iter().fold(0.0_f32, |m, s| m.max(s.abs()));
+            let target = if peak > self.ceiling {
+                self.ceiling / peak
+            } else {
+                1.0
+            };
+            while self.minimum.back().is_some_and(|(_, g)| *g >= target) {
+                self.minimum.pop_back();
+            }
+            self.minimum.push_back((self.frame, target));
+            while self
+                .minimum
+                .front()
+                .is_some_and(|(f, _)| f + window as u64 <= self.frame)
+            {
+                self.minimum.pop_front();
+            }
+            let lowest = self.minimum.front().map_or(1.0, |(_, g)| *g);
+            self.envelope = if lowest < self.envelope {
+                lowest
+            } else {
+                lowest + (self.envelope - lowest) * release
+            };
+            self.average.push_back(self.envelope);
+            self.average_sum += self.envelope as f64;
+            if self.average.len() > window {
+                self.average_sum -= self.average.pop_front().unwrap_or(0.0) as f64;
+            }
+            let gain = (self.average_sum / window as f64) as f32;
+            self.frame += 1;
+
+            self.delay.extend(frame.iter().copied());
+            for s in frame.iter_mut() {
+                let delayed = self.delay.pop_front().unwrap_or(0.0);
+                //rounding in the average may leave the tiniest overshoot.
+                *s = (delayed * gain).clamp(-self.ceiling, self.ceiling);
+            }
+        }
+    }
+
+    fn reset(&mut self) {
+        self.delay.clear();
+        self.delay
+            .extend(std::iter::repeat_n(0.0, (self.window - 1) * self.channels));
+        self.minimum.clear();
+        self.envelope = 1.0;
+        self.average.clear();
+        self.average.extend(std::iter::repeat_n(1.0, self.window));
+        self.average_sum = self.window as f64;
+        self.frame = 0;
+    }
+}
+
+//feed-forward compressor with a peak detector linked across channels.
+pub struct Compressor {
+    threshold_db: f32,
+    ratio: f32,
+    attack_ms: f32,
+    release_ms: f32,
+    makeup_db: f32,
+    //gain reduction in db, positive.
+    reduction: f32,
+}
+
+impl Compressor {
+    pub fn new(threshold_db: f32, ratio: f32) -> Self {
+        Self {
+            threshold_db,
+            ratio: ratio.max(1.0),
+            attack_ms: 10.0,
+            release_ms: 100.0,
+            makeup_db: 0.0,
+            reduction: 0.0,
+        }
+    }
+
+    pub fn threshold_db(&self) -> f32 {
+        self.threshold_db
+    }
+
+    pub fn set_threshold_db(&mut self, threshold_db: f32) {
+        self.threshold_db = threshold_db;
+    }
+
+    pub fn ratio(&self) -> f32 {
+        self.ratio
+    }
+
+    pub fn set_ratio(&mut self, ratio: f32) {
+        self.ratio = ratio.max(1.0);
+    }
+
+    pub fn attack_ms(&self) -> f32 {
+        self.attack_ms
+    }
+
+    pub fn set_attack_ms(&mut self, attack_ms: f32) {
+        self.attack_ms = attack_ms.max(0.0);
+    }
+
+    pub fn release_ms(&self) -> f32 {
+        self.release_ms
+    }
+
+    pub fn set_release_ms(&mut self, release_ms: f32) {
+        self.release_ms = release_ms.max(0.0);
+    }
+
+    pub fn makeup_db(&self) -> f32 {
+        self.makeup_db
+    }
+
+    pub fn set_makeup_db(&mut self, makeup_db: f32) {
+        self.makeup_db = makeup_db;
+    }
+
+    //current gain reduction in db, for metering.
+    pub fn reduction_db(&self) -> f32 {
+        self.reduction
+    }
+}
+
+impl Effect for Compressor {
+    fn process(&mut self, buf: &mut [f32], channels: usize, sample_rate: u32) {
+        let channels = channels.max(1);
+        let attack = time_coeff(self.attack_ms, sample_rate);
+        let release = time_coeff(self.release_ms, sample_rate);
+        let slope = 1.0 - 1.0 / self.ratio;
+        for frame in buf.chunks_exact_mut(channels) {
+            let peak = frame.iter().fold(0.0_f32, |m, s| m.max(s.abs()));
+            let over = gain_to_db(peak) - self.threshold_db;
+            let wanted = if over > 0.0 { over * slope } else { 0.0 };
+            let coeff = if wanted > self.reduction {
+                attack
+            } else {
+                release
+            };
+            self.reduction = wanted + (self.reduction - wanted) * coeff;
+            let gain = db_to_gain(self.makeup_db - self.reduction);
+            frame.iter_mut().for_each(|s| *s *= gain);
+        }
+    }
+
+    fn reset(&mut self) {
+        self.reduction = 0.0;
+    }
+}
+
+#[test]
+fn limiter_holds_the_ceiling() {
+    //three loud sines summed like a busy mix, peaking around 2.4.
+    let mut buf = super::sine(220.0, 48000, 9600, 2);
+    for ((s, a), b) in buf
+        .iter_mut()
+        .zip(super::sine(330.0, 48000, 9600, 2))
+        .zip(super::sine(550.0, 48000, 9600, 2))
+    {
+        *s = (*s + a + b) * 0.8;
+    }
+    let input = buf.clone();
+    let mut limiter = Limiter::new(-1.0, 5.0, 50.0);
+    limiter.process(&mut buf, 2, 48000);
+    let ceiling = db_to_gain(-1.0);
+    assert!(buf.iter().all(|s| s.abs() <= ceiling));
+    //output is the delayed input scaled down, never flipped or boosted.
+    let latency = limiter.latency_frames();
+    assert_eq!(latency, 239);
+    for (o, i) in buf[latency * 2..].iter().zip(input.iter()) {
+        assert!(o.abs() <= i.abs() + 1e-6 && o * i >= 0.0);
+    }
+}
+
+#[test]
+fn limiter_passes_quiet_signals() {
+    let input = super::sine(440.0, 48000, 4800, 1)
+        .iter()
+        .map(|s| s * 0.5)
+        .collect::<Vec<_>>();
+    let mut buf = input.clone();
+    let mut limiter = Limiter::new(0.0, 2.0, 50.0);
+    limiter.process(&mut buf, 1, 48000);
+    let latency = limiter.latency_frames();
+    assert_eq!(&buf[latency..], &input[..input.len() - latency]);
+}
+
+#[test]
+fn compressor_applies_ratio_above_threshold() {
+    let mut comp = Compressor::new(-20.0, 4.0);
+    comp.set_makeup_db(3.0);
+    //constant level at -8db: 12db over, so 9db of reduction once settled.
+    let mut buf = vec![db_to_gain(-8.0); 48000];
+    comp.process(&mut buf, 1, 48000);
+    assert!((comp.reduction_db() - 9.0).abs() < 0.01);
+    assert!((gain_to_db(buf[47999]) - (-8.0 - 9.0 + 3.0)).abs() < 0.01);
+    //below the threshold only the makeup is applied after release.
+    let mut buf = vec![db_to_gain(-30.0); 48000];
+    comp.process(&mut buf, 1, 48000);
+    assert!((gain_to_db(buf[47999]) - (-27.0)).abs() < 0.01);
+}
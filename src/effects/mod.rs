@@ -0,0 +1,173 @@
+//post-decode processing on interleaved f32 buffers.
+use std::io::{Read, Seek};
+
+use crate::stream::SampleStream;
+
+pub mod biquad;
+pub mod bit_crusher;
+pub mod distortion;
+pub mod dynamics;
+pub mod reverb;
+pub mod ring_mod;
+
+pub use biquad::{Biquad, FilterType};
+pub use bit_crusher::BitCrusher;
+pub use distortion::Distortion;
+pub use dynamics::{Compressor, Limiter};
+pub use reverb::Reverb;
+pub use ring_mod::RingMod;
+
+pub trait Effect {
+    //processes interleaved frames in place. the rate is passed every call so the same effect works
+    //after a decoder at the file rate or inside a mixer at the output rate.
+    fn process(&mut self, buf: &mut [f32], channels: usize, sample_rate: u32);
+
+    //forgets any signal history (delay lines, filter state).
+    fn reset(&mut self);
+}
+
+impl<E: Effect + ?Sized> Effect for Box<E> {
+    fn process(&mut self, buf: &mut [f32], channels: usize, sample_rate: u32) {
+        (**self).process(buf, channels, sample_rate);
+    }
+
+    fn reset(&mut self) {
+        (**self).reset();
+    }
+}
+
+//effects applied one after another.
+#[derive(Default)]
+pub struct EffectChain {
+    effects: Vec<Box<dyn Effect>>,
+}
+
+impl EffectChain {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn push<E: Effect + 'static>(&mut self, effect: E) {
+        self.effects.push(Box::new(effect));
+    }
+
+    pub fn with<E: Effect + 'static>(mut self, effect: E) -> Self {
+        self.push(effect);
+        self
+    }
+
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut (dyn Effect + 'static)> {
+        self.effects.get_mut(index).map(|e| e.as_mut())
+    }
+
+    pub fn remove(&mut self, index: usize) -> Box<dyn Effect> {
+        self.effects.remove(index)
+    }
+
+    pub fn clear(&mut self) {
+        self.effects.clear();
+    }
+
+    pub fn len(&self) -> usize {
+        self.effects.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.effects.is_empty()
+    }
+}
+
+impl Effect for EffectChain {
+    fn process(&mut self, buf: &mut [f32], channels: usize, sample_rate: u32) {
+        for effect in self.effects.iter_mut() {
+            effect.process(buf, channels, sample_rate);
+        }
+    }
+
+    fn reset(&mut self) {
+        for effect in self.effects.iter_mut() {
+            effect.reset();
+        }
+    }
+}
+
+//a sample stream with an effect chain on its output.
+pub struct EffectStream<R>
+where
+    R: Read + Seek,
+{
+    stream: SampleStream<R>,
+    chain: EffectChain,
+}
+
+impl<R> EffectStream<R>
+where
+    R: Read + Seek,
+{
+    pub fn new(stream: SampleStream<R>, chain: EffectChain) -> Self {
+        Self { stream, chain }
+    }
+
+    pub fn stream(&self) -> &SampleStream<R> {
+        &self.stream
+    }
+
+    pub fn stream_mut(&mut self) -> &mut SampleStream<R> {
+        &mut self.stream
+    }
+
+    pub fn chain_mut(&mut self) -> &mut EffectChain {
+        &mut self.chain
+    }
+
+    pub fn channels(&self) -> usize {
+        self.stream.channels()
+    }
+
+    pub fn sample_rate(&self) -> u32 {
+        self.stream.sample_rate()
+    }
+
+    //same as SampleStream::read, with the chain applied to the frames read.
+    pub fn read(&mut self, out: &mut [f32]) -> usize {
+        let frames = self.stream.read(out);
+        let channels = self.stream.channels();
+        let rate = self.stream.sample_rate();
+        self.chain
+            .process(&mut out[..frames * channels], channels, rate);
+        frames
+    }
+
+    pub fn into_inner(self) -> (SampleStream<R>, EffectChain) {
+        (self.stream, self.chain)
+    }
+}
+
+#[cfg(test)]
+pub(crate) fn sine(freq: f32, sample_rate: u32, frames: usize, channels: usize) -> Vec<f32> {
+    (0..frames)
+        .flat_map(|i| {
+            let s = (2.0 * std::f32::consts::PI * freq * i as f32 / sample_rate as f32).sin();
+            std::iter::repeat_n(s, channels)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+pub(crate) fn rms(buf: &[f32]) -> f32 {
+    (buf.iter().map(|s| s * s).sum::<f32>() / buf.len().max(1) as f32).sqrt()
+}
+
+#[test]
+fn effect_stream_filters_decoder_output() {
+    let samples = (0..4000)
+        .map(|i| if i % 2 == 0 { 8000 } else { -8000 })
+        .collect::<Vec<i16>>();
+    let file = crate::test_util::pcm16_cks(1, 8000, &samples, (0, 0, 0));
+    let decoder = crate::decoder::Decoder::new(std::io::Cursor::new(file)).unwrap();
+    let chain = EffectChain::new().with(Biquad::low_pass(500.0, 0.707));
+    let mut stream = EffectStream::new(SampleStream::new(decoder), chain);
+    let mut out = vec![0.0; 4000];
+    assert_eq!(stream.read(&mut out), 4000);
+    assert!(rms(&out[1000..]) < 0.01);
+}
@@ -0,0 +1,289 @@
+//freeverb: eight parallel damped combs into four series allpasses per side, with a mono pre-delay.
+//pure f32 arithmetic with no randomness, so renders are bit-for-bit repeatable.
+use super::Effect;
+
+//tunings are in frames at 44.1khz and scaled to the processing rate.
+static COMB_TUNING: [usize; 8] = [1116, 1188, 1277, 1356, 1422, 1491, 1557, 1617];
+static ALLPASS_TUNING: [usize; 4] = [556, 441, 341, 225];
+static STEREO_SPREAD: usize = 23;
+static INPUT_GAIN: f32 = 0.015;
+static ALLPASS_FEEDBACK: f32 = 0.5;
+
+struct Comb {
+    buf: Vec<f32>,
+    pos: usize,
+    store: f32,
+}
+
+impl Comb {
+    fn new(len: usize) -> Self {
+        Self {
+            buf: vec![0.0; len.max(1)],
+            pos: 0,
+            store: 0.0,
+        }
+    }
+
+    fn process(&mut self, input: f32, feedback: f32, damp: f32) -> f32 {
+        let out = self.buf[self.pos];
+        self.store = out * (1.0 - damp) + self.store * damp;
+        self.buf[self.pos] = input + self.store * feedback;
+        self.pos = (self.pos + 1) % self.buf.len();
+        out
+    }
+}
+
+struct Allpass {
+    buf: Vec<f32>,
+    pos: usize,
+}
+
+impl Allpass {
+    fn new(len: usize) -> Self {
+        Self {
+            buf: vec![0.0; len.max(1)],
+            pos: 0,
+        }
+    }
+
+    fn process(&mut self, input: f32) -> f32 {
+        let delayed = self.buf[self.pos];
+        self.buf[self.pos] = input + delayed * ALLPASS_FEEDBACK;
+        self.pos = (self.pos + 1) % self.buf.len();
+        delayed - input
+    }
+}
+
+struct Side {
+    combs: Vec<Comb>,
+    allpasses: Vec<Allpass>,
+}
+
+impl Side {
+    fn new(sample_rate: u32, spread: usize) -> Self {
+        let scale = |n: usize| ((n + spread) as u64 * sample_rate as u64 / 44100) as usize;
+        Self {
+            combs: COMB_TUNING.iter().map(|n| Comb::new(scale(*n))).collect(),
+            allpasses: ALLPASS_TUNING
+                .iter()
+                .map(|n| Allpass::new(scale(*n)))
+                .collect(),
+        }
+    }
+
+    fn process(&mut self, input: f32, feedback: f32, damp: f32) -> f32 {
+        let mut out = 0.0;
+        for comb in self.combs.iter_mut() {
+            out += comb.process(input, feedback, damp);
+        }
+        for allpass in self.allpasses.iter_mut() {
+            out = allpass.process(out);
+        }
+        out
+    }
+}
+
+pub struct Reverb {
+    room_size: f32,
+    damping: f32,
+    wet: f32,
+    dry: f32,
+    width: f32,
+    pre_delay_ms: f32,
+    sample_rate: u32,
+    sides: Option<[Side; 2]>,
+    pre_delay: Vec<f32>,
+    pre_delay_pos: usize,
+}
+
+impl Default for Reverb {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Reverb {
+    pub fn new() -> Self {
+        Self {
+            room_size: 0.5,
+            damping: 0.5,
+            wet: 0.33,
+            dry: 1.0,
+            width: 1.0,
+            pre_delay_ms: 0.0,
+            sample_rate: 0,
+            sides: None,
+            pre_delay: Vec::new(),
+            pre_delay_pos: 0,
+        }
+    }
+
+    pub fn room_size(&self) -> f32 {
+        self.room_size
+    }
+
+    //0..1, larger rooms ring longer.
+    pub fn set_room_size(&mut self, room_size: f32) {
+        self.room_size = room_size.clamp(0.0, 1.0);
+    }
+
+    pub fn damping(&self) -> f32 {
+        self.damping
+    }
+
+    //0..1, how fast high frequencies die out in the tail.
+    pub fn set_damping(&mut self, damping: f32) {
+        self.damping = damping.clamp(0.0, 1.0);
+    }
+
+    pub fn wet(&self) -> f32 {
+        self.wet
+    }
+
+    pub fn set_wet(&mut self, wet: f32) {
+        self.wet = wet.max(0.0);
+    }
+
+    pub fn dry(&self) -> f32 {
+        self.dry
+    }
+
+    pub fn set_dry(&mut self, dry: f32) {
+        self.dry = dry.max(0.0);
+    }
+
+    pub fn width(&self) -> f32 {
+        self.width
+    }
+
+    //0 is a mono tail, 1 keeps both sides fully apart.
+    pub fn set_width(&mut self, width: f32) {
+        self.width = width.clamp(0.0, 1.0);
+    }
+
+    pub fn pre_delay_ms(&self) -> f32 {
+        self.pre_delay_ms
+    }
+
+    //resizing the pre-delay line drops what is inside it.
+    pub fn set_pre_delay_ms(&mut self, pre_delay_ms: f32) {
+        self.pre_delay_ms = pre_delay_ms.max(0.0);
+        self.resize_pre_delay();
+    }
+
+    fn resize_pre_delay(&mut self) {
+        let frames = (self.pre_delay_ms * 0.001 * self.sample_rate as f32).round() as usize;
+        self.pre_delay = vec![0.0; frames];
+        self.pre_delay_pos = 0;
+    }
+
+    fn prepare(&mut self, sample_rate: u32) {
+        if self.sides.is_none() || self.sample_rate != sample_rate {
+            self.sample_rate = sample_rate;
+            self.sides = Some([
+                Side::new(sample_rate, 0),
+                Side::new(sample_rate, STEREO_SPREAD),
+            ]);
+            self.resize_pre_delay();
+        }
+    }
+}
+
+impl Effect for Reverb {
+    //mono buffers get the sum of both tails, wider buffers only have their first two channels touched.
+    fn process(&mut self, buf: &mut [f32], channels: usize, sample_rate: u32) {
+        let channels = channels.max(1);
+        self.prepare(sample_rate);
+        let feedback = self.room_size * 0.28 + 0.7;
+        let damp = self.damping * 0.4;
+        let wet1 = self.wet * (self.width * 0.5 + 0.5);
+        let wet2 = self.wet * ((1.0 - self.width) * 0.5);
+        let Some([left, right]) = self.sides.as_mut() else {
+            return;
+        };
+        for frame in buf.chunks_exact_mut(channels) {
+            let input = if channels == 1 {
+                frame[0]
+            } else {
+                frame[0] + frame[1]
+            } * INPUT_GAIN;
+            //an empty line is 0 ms; otherwise the slot read back was written one line length ago.
+            let input = if self.pre_delay.is_empty() {
+                input
+            } else {
+                let delayed = self.pre_delay[self.pre_delay_pos];
+                self.pre_delay[self.pre_delay_pos] = input;
+                self.pre_delay_pos = (self.pre_delay_pos + 1) % self.pre_delay.len();
+                delayed
+            };
+            let l = left.process(input, feedback, damp);
+            let r = right.process(input, feedback, damp);
+            if channels == 1 {
+                frame[0] = frame[0] * self.dry + (l + r) * 0.5 * self.wet;
+            } else {
+                frame[0] = frame[0] * self.dry + l * wet1 + r * wet2;
+                frame[1] = frame[1] * self.dry + r * wet1 + l * wet2;
+            }
+        }
+    }
+
+    fn reset(&mut self) {
+        self.sides = None;
+        self.pre_delay.iter_mut().for_each(|s| *s = 0.0);
+        self.pre_delay_pos = 0;
+    }
+}
+
+#[cfg(test)]
+fn impulse_response(reverb: &mut Reverb, frames: usize) -> Vec<f32> {
+    let mut buf = vec![0.0; frames * 2];
+    buf[0] = 1.0;
+    buf[1] = 1.0;
+    reverb.process(&mut buf, 2, 48000);
+    buf
+}
+
+#[test]
+fn reverb_is_deterministic() {
+    let mut input = super::sine(440.0, 48000, 4800, 2);
+    input.extend(std::iter::repeat_n(0.0, 48000 * 2));
+    let mut a = input.clone();
+    let mut reverb = Reverb::new();
+    reverb.process(&mut a, 2, 48000);
+    reverb.reset();
+    let mut b = input.clone();
+    //different block sizes must not change the result.
+    for chunk in b.chunks_mut(2 * 333) {
+        reverb.process(chunk, 2, 48000);
+    }
+    assert_eq!(a, b);
+    assert!(super::rms(&a[2 * 4800..2 * 9600]) > 0.01);
+}
+
+#[test]
+fn reverb_pre_delay_holds_back_the_tail() {
+    let onset = |pre_delay_ms: f32| {
+        let mut reverb = Reverb::new();
+        reverb.set_dry(0.0);
+        reverb.set_wet(1.0);
+        reverb.set_pre_delay_ms(pre_delay_ms);
+        let out = impulse_response(&mut reverb, 9600);
+        out.iter().position(|s| *s != 0.0).unwrap() / 2
+    };
+    //shortest comb at 48khz is 1214 frames, 50 ms adds exactly 2400 frames on top.
+    assert_eq!(onset(0.0), 1214);
+    assert_eq!(onset(50.0), 1214 + 2400);
+}
+
+#[test]
+fn reverb_room_size_lengthens_the_tail() {
+    let tail = |room: f32| {
+        let mut reverb = Reverb::new();
+        reverb.set_dry(0.0);
+        reverb.set_room_size(room);
+        reverb.set_damping(0.2);
+        let out = impulse_response(&mut reverb, 96000);
+        super::rms(&out[2 * 72000..])
+    };
+    assert!(tail(0.9) > tail(0.2) * 10.0);
+}
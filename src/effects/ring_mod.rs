@@ -0,0 +1,63 @@
+//multiplies the signal with a sine carrier.
+use super::Effect;
+
+pub struct RingMod {
+    freq: f32,
+    wet: f32,
+    phase: f64,
+}
+
+impl RingMod {
+    pub fn new(freq: f32) -> Self {
+        Self {
+            freq: freq.max(0.0),
+            wet: 1.0,
+            phase: 0.0,
+        }
+    }
+
+    pub fn frequency(&self) -> f32 {
+        self.freq
+    }
+
+    //carrier frequency in hz.
+    pub fn set_frequency(&mut self, freq: f32) {
+        self.freq = freq.max(0.0);
+    }
+
+    pub fn wet(&self) -> f32 {
+        self.wet
+    }
+
+    //0..1, 0 passes the input through.
+    pub fn set_wet(&mut self, wet: f32) {
+        self.wet = wet.clamp(0.0, 1.0);
+    }
+}
+
+impl Effect for RingMod {
+    fn process(&mut self, buf: &mut [f32], channels: usize, sample_rate: u32) {
+        let channels = channels.max(1);
+        let step = self.freq as f64 / sample_rate.max(1) as f64;
+        for frame in buf.chunks_exact_mut(channels) {
+            let carrier = (self.phase * std::f64::consts::TAU).sin() as f32;
+            let gain = 1.0 - self.wet + carrier * self.wet;
+            frame.iter_mut().for_each(|s| *s *= gain);
+            self.phase = (self.phase + step).fract();
+        }
+    }
+
+    fn reset(&mut self) {
+        self.phase = 0.0;
+    }
+}
+
+#[test]
+fn ring_mod_follows_the_carrier() {
+    let mut buf = vec![1.0; 2 * 8];
+    RingMod::new(2000.0).process(&mut buf, 2, 8000);
+    let expected = [0.0, 1.0, 0.0, -1.0, 0.0, 1.0, 0.0, -1.0];
+    for (frame, e) in buf.chunks_exact(2).zip(expected) {
+        assert!((frame[0] - e).abs() < 1e-6 && frame[0] == frame[1]);
+    }
+}
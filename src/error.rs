@@ -6,6 +6,7 @@ pub enum CksError {
     UnsupportedDecType,
     Io,
     InsufficientData,
+    InvalidBlock,
     SkippedData,
     EoF,
 }
@@ -14,4 +15,5 @@ pub enum CksError {
 pub enum AdpcmError {
     InvalidStride,
     NoEnoughInputBytes,
+    InvalidPredictor,
 }
@@ -1,48 +1,61 @@
 use crate::error::CksError;
-use std::io::{Read, Seek};
+use crate::sample::info::{SampleInfo, SAMPLE_INFO_BYTES};
+
+pub(crate) const FILE_HEADER_BYTES: usize = 16;
+
+//file header and sample info together; block data starts right after.
+pub(crate) const HEADER_BYTES: usize = FILE_HEADER_BYTES + SAMPLE_INFO_BYTES;
 
 pub struct FileHeader {
-    marker: String,
+    marker: [u8; 4],
     targets: u32,
     file_type: u32,
     file_version: u32,
 }
 
 impl FileHeader {
-    pub(crate) fn new<R: Read + Seek>(mut reader: R) -> Result<Self, CksError> {
+    #[cfg(feature = "std")]
+    pub(crate) fn new<R: std::io::Read + std::io::Seek>(mut reader: R) -> Result<Self, CksError> {
+        let mut buf = [0u8; FILE_HEADER_BYTES];
+        reader.read_exact(&mut buf).or(Err(CksError::FileRead))?;
+        Ok(Self::from_bytes(&buf))
+    }
+
+    pub(crate) fn from_bytes(buf: &[u8; 16]) -> Self {
         let mut buffer_unit = [0u8; 4];
-        let mut marker = String::with_capacity(4);
         let mut targets = 0;
         let mut file_type = 0;
         let mut file_version = 0;
 
-        reader
-            .read_exact(&mut buffer_unit)
-            .or(Err(CksError::FileRead))?;
-        for c in buffer_unit {
-            marker.push(c as char);
-        }
-
-        reader
-            .read_exact(&mut buffer_unit)
-            .or(Err(CksError::FileRead))?;
+        let marker = [buf[0], buf[1], buf[2], buf[3]];
+        buffer_unit.copy_from_slice(&buf[4..8]);
         write_header_info(&buffer_unit, &mut targets);
-        reader
-            .read_exact(&mut buffer_unit)
-            .or(Err(CksError::FileRead))?;
+        buffer_unit.copy_from_slice(&buf[8..12]);
         write_header_info(&buffer_unit, &mut file_type);
-        reader
-            .read_exact(&mut buffer_unit)
-            .or(Err(CksError::FileRead))?;
+        buffer_unit.copy_from_slice(&buf[12..16]);
         write_header_info(&buffer_unit, &mut file_version);
 
-        Ok(Self {
+        Self {
             marker,
             targets,
             file_type,
             file_version,
-        })
+        }
+    }
+
+    pub(crate) fn is_cks(&self) -> bool {
+        &self.marker == b"ckmk"
+    }
+}
+
+//checks the file header at the start of bytes and reads the sample info after it.
+pub(crate) fn parse_header(bytes: &[u8]) -> Result<SampleInfo, CksError> {
+    let bytes = bytes.get(..HEADER_BYTES).ok_or(CksError::FileRead)?;
+    let (header, info) = bytes.split_at(FILE_HEADER_BYTES);
+    if !FileHeader::from_bytes(header.try_into().unwrap()).is_cks() {
+        return Err(CksError::NotCksFile);
     }
+    Ok(SampleInfo::from_bytes(info.try_into().unwrap()))
 }
 
 #[inline]
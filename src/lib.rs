@@ -1,13 +1,53 @@
 #![allow(dead_code, unused)]
+//without std only the parsing and decoding core is built, over ByteSource instead of io::Read.
+#![cfg_attr(not(feature = "std"), no_std)]
+extern crate alloc;
+
+#[cfg(feature = "async")]
+pub mod async_decoder;
 mod audio_util;
+#[cfg(feature = "std")]
 pub mod decoder;
 mod decoder_core;
-mod error;
+#[cfg(feature = "std")]
+pub mod effects;
+pub mod error;
 mod file_header;
+#[cfg(feature = "std")]
+pub mod mixer;
+pub mod push_decoder;
+#[cfg(feature = "std")]
+pub mod resample;
+pub mod rng;
+#[cfg(feature = "rodio")]
+pub mod rodio_source;
 pub mod sample;
+#[cfg(feature = "scene")]
+pub mod scene;
+#[cfg(feature = "std")]
+pub mod sequence;
+#[cfg(feature = "std")]
+pub mod slice_decoder;
+pub mod source;
+#[cfg(feature = "std")]
+pub mod spatial;
+#[cfg(feature = "std")]
+pub mod stream;
+#[cfg(feature = "symphonia")]
+pub mod symphonia;
+#[cfg(test)]
+mod test_util;
 
-#[cfg(feature = "time-stretch")]
+#[cfg(any(feature = "time-stretch", feature = "time-stretch-native"))]
 pub mod time_stretch;
+#[cfg(feature = "std")]
+pub mod variation;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+#[cfg(feature = "std")]
+pub mod wav;
+
+use alloc::{vec, vec::Vec};
 
 #[derive(PartialEq, Debug)]
 pub enum FormatType {
@@ -0,0 +1,133 @@
+//named nodes of the mixer tree; gain, mute and pause apply to every sound below a group.
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct GroupId(pub(crate) usize);
+
+//length of the gain ramp applied when a group's volume or mute changes.
+pub(crate) static RAMP_MS: f32 = 10.0;
+
+pub struct MixerGroup {
+    name: String,
+    parent: Option<GroupId>,
+    volume: f32,
+    muted: bool,
+    paused: bool,
+    pub(crate) ramp: GainRamp,
+}
+
+impl MixerGroup {
+    pub(crate) fn new(name: &str, parent: Option<GroupId>) -> Self {
+        Self {
+            name: name.to_string(),
+            parent,
+            volume: 1.0,
+            muted: false,
+            paused: false,
+            ramp: GainRamp::new(1.0),
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    //None only for the master group.
+    pub fn parent(&self) -> Option<GroupId> {
+        self.parent
+    }
+
+    pub fn volume(&self) -> f32 {
+        self.volume
+    }
+
+    pub fn set_volume(&mut self, volume: f32) {
+        self.volume = volume.max(0.0);
+    }
+
+    pub fn is_muted(&self) -> bool {
+        self.muted
+    }
+
+    pub fn set_muted(&mut self, muted: bool) {
+        self.muted = muted;
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    //sounds below a paused group keep their position until it is resumed.
+    pub fn set_paused(&mut self, paused: bool) {
+        self.paused = paused;
+    }
+
+    pub(crate) fn target_gain(&self) -> f32 {
+        if self.muted {
+            0.0
+        } else {
+            self.volume
+        }
+    }
+}
+
+//linear per-frame ramp towards a target gain.
+#[derive(Clone, Debug)]
+pub(crate) struct GainRamp {
+    current: f32,
+    target: f32,
+    step: f32,
+    frames_left: usize,
+}
+
+impl GainRamp {
+    pub(crate) fn new(gain: f32) -> Self {
+        Self {
+            current: gain,
+            target: gain,
+            step: 0.0,
+            frames_left: 0,
+        }
+    }
+
+    pub(crate) fn current(&self) -> f32 {
+        self.current
+    }
+
+    pub(crate) fn target(&self) -> f32 {
+        self.target
+    }
+
+    pub(crate) fn is_ramping(&self) -> bool {
+        self.frames_left > 0
+    }
+
+    pub(crate) fn set_target(&mut self, target: f32, frames: usize) {
+        if target == self.target {
+            return;
+        }
+        self.target = target;
+        if frames == 0 {
+            self.current = target;
+            self.frames_left = 0;
+        } else {
+            self.step = (target - self.current) / frames as f32;
+            self.frames_left = frames;
+        }
+    }
+
+    pub(crate) fn next_gain(&mut self) -> f32 {
+        if self.frames_left > 0 {
+            self.frames_left -= 1;
+            self.current = if self.frames_left == 0 {
+                self.target
+            } else {
+                self.current + self.step
+            };
+        }
+        self.current
+    }
+}
+
+pub(crate) fn ramp_frames(sample_rate: u32) -> usize {
+    (sample_rate as f32 * RAMP_MS / 1000.0) as usize
+}
@@ -0,0 +1,764 @@
+//offline software mixer: owns sound instances and sums them into a stereo f32 buffer.
+use std::io::{Read, Seek};
+
+use crate::decoder::Decoder;
+use crate::effects::{Effect, EffectChain};
+use crate::spatial::Listener;
+use schedule::{Action, Envelope};
+
+mod group;
+mod schedule;
+mod sound;
+mod voice;
+pub use group::{GroupId, MixerGroup};
+pub use schedule::FadeCurve;
+pub use sound::{PlayState, Sound};
+pub use voice::{StealPolicy, VoiceLimits};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct SoundId(u64);
+
+pub struct Mixer<R>
+where
+    R: Read + Seek,
+{
+    sample_rate: u32,
+    sounds: Vec<(SoundId, Sound<R>)>,
+    next_id: u64,
+    //parents always come before their children, so index order walks the tree top-down.
+    groups: Vec<MixerGroup>,
+    group_gains: Vec<Vec<f32>>,
+    group_paused: Vec<bool>,
+    effects: EffectChain,
+    listener: Listener,
+    voices: VoiceLimits,
+    play_serial: u64,
+    //output frames rendered so far.
+    clock: u64,
+    //sorted by frame, events on the same frame in the order they were scheduled.
+    events: Vec<schedule::Event>,
+}
+
+impl<R> Mixer<R>
+where
+    R: Read + Seek,
+{
+    pub fn new(sample_rate: u32) -> Self {
+        Self {
+            sample_rate,
+            sounds: Vec::new(),
+            next_id: 0,
+            groups: vec![MixerGroup::new("master", None)],
+            group_gains: Vec::new(),
+            group_paused: Vec::new(),
+            effects: EffectChain::new(),
+            listener: Listener::default(),
+            voices: VoiceLimits::default(),
+            play_serial: 0,
+            clock: 0,
+            events: Vec::new(),
+        }
+    }
+
+    pub fn master(&self) -> GroupId {
+        GroupId(0)
+    }
+
+    pub fn add_group(&mut self, name: &str, parent: GroupId) -> GroupId {
+        let parent = if parent.0 < self.groups.len() {
+            parent
+        } else {
+            self.master()
+        };
+        self.groups.push(MixerGroup::new(name, Some(parent)));
+        GroupId(self.groups.len() - 1)
+    }
+
+    pub fn group(&self, id: GroupId) -> Option<&MixerGroup> {
+        self.groups.get(id.0)
+    }
+
+    pub fn group_mut(&mut self, id: GroupId) -> Option<&mut MixerGroup> {
+        self.groups.get_mut(id.0)
+    }
+
+    pub fn find_group(&self, name: &str) -> Option<GroupId> {
+        self.groups
+            .iter()
+            .position(|g| g.name() == name)
+            .map(GroupId)
+    }
+
+    pub fn set_group(&mut self, sound: SoundId, group: GroupId) {
+        if group.0 >= self.groups.len() {
+            return;
+        }
+        if let Some(s) = self.sound_mut(sound) {
+            s.group = group;
+        }
+    }
+
+    //master bus effects, run on the finished stereo mix.
+    pub fn effects_mut(&mut self) -> &mut EffectChain {
+        &mut self.effects
+    }
+
+    pub fn listener(&self) -> &Listener {
+        &self.listener
+    }
+
+    //positioned sounds are re-spatialized against the listener once per render call.
+    pub fn listener_mut(&mut self) -> &mut Listener {
+        &mut self.listener
+    }
+
+    pub fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    pub fn set_sample_rate(&mut self, sample_rate: u32) {
+        self.sample_rate = sample_rate;
+    }
+
+    //the sound starts stopped; call play on it.
+    pub fn add(&mut self, decoder: Decoder<R>) -> SoundId {
+        self.add_sound(Sound::new(decoder))
+    }
+
+    pub fn add_sound(&mut self, sound: Sound<R>) -> SoundId {
+        let id = SoundId(self.next_id);
+        self.next_id += 1;
+        self.sounds.push((id, sound));
+        id
+    }
+
+    pub fn remove(&mut self, id: SoundId) -> Option<Sound<R>> {
+        let index = self.sounds.iter().position(|(i, _)| *i == id)?;
+        Some(self.sounds.remove(index).1)
+    }
+
+    pub fn sound(&self, id: SoundId) -> Option<&Sound<R>> {
+        self.sounds.iter().find(|(i, _)| *i == id).map(|(_, s)| s)
+    }
+
+    pub fn sound_mut(&mut self, id: SoundId) -> Option<&mut Sound<R>> {
+        self.sounds
+            .iter_mut()
+            .find(|(i, _)| *i == id)
+            .map(|(_, s)| s)
+    }
+
+    pub fn ids(&self) -> impl Iterator<Item = SoundId> + '_ {
+        self.sounds.iter().map(|(id, _)| *id)
+    }
+
+    pub fn len(&self) -> usize {
+        self.sounds.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.sounds.is_empty()
+    }
+
+    pub fn voice_limits(&self) -> &VoiceLimits {
+        &self.voices
+    }
+
+    pub fn voice_limits_mut(&mut self) -> &mut VoiceLimits {
+        &mut self.voices
+    }
+
+    //sounds playing and not fading out after being stolen.
+    pub fn voice_count(&self) -> usize {
+        self.sounds.iter().filter(|(_, s)| is_voice(s)).count()
+    }
+
+    //starts or resumes a sound, stealing a voice when a limit is reached.
+    //returns false when every voice in the way outranks it, the sound then stays as it was.
+    pub fn play(&mut self, id: SoundId) -> bool {
+        let Some(index) = self.sounds.iter().position(|(i, _)| *i == id) else {
+            return false;
+        };
+        let sound = &self.sounds[index].1;
+        if is_voice(sound) {
+            return true;
+        }
+        let priority = sound.priority();
+        if let Some(asset) = sound.asset().map(str::to_string) {
+            if let Some(limit) = self.voices.asset_limit(&asset) {
+                let same_asset = |s: &Sound<R>| s.asset() == Some(asset.as_str());
+                if !self.make_room(index, limit, priority, same_asset) {
+                    return false;
+                }
+            }
+        }
+        if let Some(limit) = self.voices.max_voices() {
+            if !self.make_room(index, limit, priority, |_| true) {
+                return false;
+            }
+        }
+        self.play_serial += 1;
+        let sound = &mut self.sounds[index].1;
+        sound.started = self.play_serial;
+        sound.play();
+        true
+    }
+
+    pub fn pause(&mut self, id: SoundId) {
+        if let Some(s) = self.sound_mut(id) {
+            s.pause();
+        }
+    }
+
+    pub fn stop(&mut self, id: SoundId) {
+        if let Some(s) = self.sound_mut(id) {
+            s.stop();
+        }
+    }
+
+    //output frames rendered since the mixer was created; the timeline of scheduled events.
+    pub fn time(&self) -> u64 {
+        self.clock
+    }
+
+    //events at frames already rendered happen at the start of the next render call.
+    pub fn schedule_play(&mut self, id: SoundId, at: u64) {
+        self.schedule(at, id, Action::Play);
+    }
+
+    pub fn schedule_stop(&mut self, id: SoundId, at: u64) {
+        self.schedule(at, id, Action::Stop);
+    }
+
+    //plays from silence up to full gain.
+    pub fn fade_in(&mut self, id: SoundId, at: u64, frames: usize, curve: FadeCurve) {
+        self.schedule(at, id, Action::Play);
+        self.fade(id, at, Some(0.0), 1.0, frames, curve, false);
+    }
+
+    //fades to silence and stops.
+    pub fn fade_out(&mut self, id: SoundId, at: u64, frames: usize, curve: FadeCurve) {
+        self.fade(id, at, None, 0.0, frames, curve, true);
+    }
+
+    //fades from wherever the envelope is to gain, which is then held.
+    pub fn fade_to(&mut self, id: SoundId, at: u64, frames: usize, gain: f32, curve: FadeCurve) {
+        self.fade(id, at, None, gain.max(0.0), frames, curve, false);
+    }
+
+    pub fn crossfade(
+        &mut self,
+        from: SoundId,
+        to: SoundId,
+        at: u64,
+        frames: usize,
+        curve: FadeCurve,
+    ) {
+        self.fade_out(from, at, frames, curve);
+        self.fade_in(to, at, frames, curve);
+    }
+
+    //drops every event still pending for the sound.
+    pub fn cancel_scheduled(&mut self, id: SoundId) {
+        self.events.retain(|e| e.sound != id);
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn fade(
+        &mut self,
+        id: SoundId,
+        at: u64,
+        from: Option<f32>,
+        to: f32,
+        frames: usize,
+        curve: FadeCurve,
+        stop_at_end: bool,
+    ) {
+        let action = Action::Fade {
+            from,
+            to,
+            frames,
+            curve,
+            stop_at_end,
+        };
+        self.schedule(at, id, action);
+    }
+
+    fn schedule(&mut self, frame: u64, sound: SoundId, action: Action) {
+        let index = self.events.partition_point(|e| e.frame <= frame);
+        let event = schedule::Event {
+            frame,
+            sound,
+            action,
+        };
+        self.events.insert(index, event);
+    }
+
+    fn run_events(&mut self) {
+        let due = self.events.partition_point(|e| e.frame <= self.clock);
+        let events = self.events.drain(..due).collect::<Vec<_>>();
+        for event in events {
+            match event.action {
+                Action::Play => {
+                    self.play(event.sound);
+                }
+                Action::Stop => self.stop(event.sound),
+                Action::Fade {
+                    from,
+                    to,
+                    frames,
+                    curve,
+                    stop_at_end,
+                } => {
+                    if let Some(sound) = self.sound_mut(event.sound) {
+                        let from = from.unwrap_or(sound.envelope_gain());
+                        sound.set_envelope(Envelope::new(from, to, frames, curve, stop_at_end));
+                    }
+                }
+            }
+        }
+    }
+
+    //steals voices matching the filter until fewer than limit are left, besides the one at index.
+    fn make_room<F>(&mut self, index: usize, limit: usize, priority: i32, filter: F) -> bool
+    where
+        F: Fn(&Sound<R>) -> bool,
+    {
+        loop {
+            let voices = self
+                .sounds
+                .iter()
+                .enumerate()
+                .filter(|(i, (_, s))| *i != index && is_voice(s) && filter(s))
+                .map(|(i, (_, s))| (i, s))
+                .collect::<Vec<_>>();
+            if voices.len() < limit {
+                return true;
+            }
+            let candidates = voices
+                .iter()
+                .filter(|(_, s)| s.priority() <= priority)
+                .map(|(i, s)| voice::Candidate {
+                    index: *i,
+                    priority: s.priority(),
+                    started: s.started,
+                    audibility: s.audibility() * self.group_gain(s.group),
+                })
+                .collect::<Vec<_>>();
+            let Some(victim) = voice::choose_victim(self.voices.policy(), &candidates) else {
+                return false;
+            };
+            let fade = (self.voices.fade_ms() * 0.001 * self.sample_rate as f32) as usize;
+            self.sounds[victim].1.stop_with_fade(fade);
+        }
+    }
+
+    //target gain of a group including everything above it.
+    fn group_gain(&self, id: GroupId) -> f32 {
+        let mut gain = 1.0;
+        let mut next = Some(id);
+        while let Some(g) = next.and_then(|g| self.groups.get(g.0)) {
+            gain *= g.target_gain();
+            next = g.parent();
+        }
+        gain
+    }
+
+    //overwrites out with the mix of every playing sound, stereo interleaved at the mixer rate.
+    //the buffer is split at scheduled events, so they land on their exact frame.
+    pub fn render(&mut self, out: &mut [f32]) {
+        let frames = out.len() / 2;
+        let mut pos = 0;
+        while pos < frames {
+            self.run_events();
+            let end = match self.events.first() {
+                Some(e) => std::cmp::min(frames, pos + (e.frame - self.clock) as usize),
+                None => frames,
+            };
+            self.render_block(&mut out[pos * 2..end * 2]);
+            self.clock += (end - pos) as u64;
+            pos = end;
+        }
+    }
+
+    fn render_block(&mut self, out: &mut [f32]) {
+        out.iter_mut().for_each(|s| *s = 0.0);
+        let frames = out.len() / 2;
+        self.update_groups(frames);
+        for (_, sound) in self.sounds.iter_mut() {
+            let group = sound.group.0;
+            if !self.group_paused[group] {
+                sound.update_spatial(&self.listener);
+                sound.mix_into(out, self.sample_rate, &self.group_gains[group]);
+            }
+        }
+        self.effects.process(out, 2, self.sample_rate);
+        self.sounds.retain(|(_, s)| {
+            !(s.is_one_shot() && s.started != 0 && s.state() == PlayState::Stopped)
+        });
+    }
+
+    //per-frame gain and pause state of every group, including everything above it.
+    fn update_groups(&mut self, frames: usize) {
+        let ramp = group::ramp_frames(self.sample_rate);
+        self.group_gains.resize(self.groups.len(), Vec::new());
+        self.group_paused.resize(self.groups.len(), false);
+        for i in 0..self.groups.len() {
+            let mut gains = std::mem::take(&mut self.group_gains[i]);
+            gains.resize(frames, 0.0);
+            let group = &mut self.groups[i];
+            let target = group.target_gain();
+            group.ramp.set_target(target, ramp);
+            for g in gains.iter_mut() {
+                *g = group.ramp.next_gain();
+            }
+            let mut paused = group.is_paused();
+            if let Some(parent) = group.parent() {
+                paused |= self.group_paused[parent.0];
+                for (g, p) in gains.iter_mut().zip(self.group_gains[parent.0].iter()) {
+                    *g *= p;
+                }
+            }
+            self.group_gains[i] = gains;
+            self.group_paused[i] = paused;
+        }
+    }
+}
+
+fn is_voice<R: Read + Seek>(sound: &Sound<R>) -> bool {
+    sound.is_playing() && !sound.is_fading_out()
+}
+
+#[cfg(test)]
+fn constant_sound(
+    channels: u8,
+    sample_rate: u16,
+    value: i16,
+    frames: usize,
+) -> Decoder<std::io::Cursor<Vec<u8>>> {
+    let samples = vec![value; frames * channels as usize];
+    let file = crate::test_util::pcm16_cks(channels, sample_rate, &samples, (0, 0, 0));
+    Decoder::new(std::io::Cursor::new(file)).unwrap()
+}
+
+#[test]
+fn mixer_sums_playing_sounds() {
+    let mut mixer = Mixer::new(8000);
+    let a = mixer.add(constant_sound(2, 8000, 8192, 100));
+    let b = mixer.add(constant_sound(2, 8000, 4096, 50));
+    let c = mixer.add(constant_sound(2, 8000, 4096, 100));
+    mixer.play(a);
+    mixer.play(b);
+    mixer.sound_mut(a).unwrap().set_pan(1.0);
+    let mut out = vec![0.0; 2 * 80];
+    mixer.render(&mut out);
+    let unit = 1.0 / i16::MAX as f32;
+    assert!((out[0] - 4096.0 * unit).abs() < 1e-6);
+    assert!((out[1] - 12288.0 * unit).abs() < 1e-6);
+    assert!((out[2 * 60] - 0.0).abs() < 1e-6);
+    assert!((out[2 * 60 + 1] - 8192.0 * unit).abs() < 1e-6);
+    assert_eq!(mixer.sound(b).unwrap().state(), PlayState::Stopped);
+    assert_eq!(mixer.sound(c).unwrap().state(), PlayState::Stopped);
+}
+
+#[test]
+fn mixer_pauses_and_resumes() {
+    let mut mixer = Mixer::new(8000);
+    let a = mixer.add(constant_sound(1, 8000, 8192, 100));
+    mixer.play(a);
+    let mut out = vec![0.0; 2 * 40];
+    mixer.render(&mut out);
+    mixer.pause(a);
+    mixer.render(&mut out);
+    assert!(out.iter().all(|s| *s == 0.0));
+    mixer.play(a);
+    mixer.render(&mut out);
+    assert!(out.iter().all(|s| *s > 0.0));
+    mixer.render(&mut out);
+    assert!(out[..40].iter().all(|s| *s > 0.0));
+    assert!(out[40..].iter().all(|s| *s == 0.0));
+}
+
+#[test]
+fn mixer_resamples_to_output_rate() {
+    let mut mixer = Mixer::new(44100);
+    let a = mixer.add(constant_sound(1, 22050, 1000, 441));
+    mixer.play(a);
+    let mut out = vec![0.0; 2 * 1000];
+    mixer.render(&mut out);
+    let frames = out.chunks(2).take_while(|f| f[0] != 0.0).count();
+    assert!((880..=884).contains(&frames), "{}", frames);
+}
+
+#[test]
+fn sound_starts_from_file_volume_and_pan() {
+    let mut info = crate::sample::info::SampleInfo {
+        format: crate::decoder::DecoderType::Pcmi16,
+        channels: 1,
+        sample_rate: 8000,
+        blocks: 4,
+        block_bytes: 2,
+        block_frames: 1,
+        volume: u16::MAX / 2,
+        pan: i16::MIN,
+        loop_start: 0,
+        loop_end: 0,
+        loop_count: 0,
+    };
+    let file = crate::test_util::cks_file(&info, &[0; 8]);
+    let sound = Sound::new(Decoder::new(std::io::Cursor::new(file)).unwrap());
+    assert!((sound.volume() - 0.5).abs() < 1e-3);
+    assert_eq!(sound.pan(), -1.0);
+}
+
+#[test]
+fn group_gain_propagates_to_children() {
+    let mut mixer = Mixer::new(8000);
+    let music = mixer.add_group("music", mixer.master());
+    let stems = mixer.add_group("stems", music);
+    let a = mixer.add(constant_sound(2, 8000, 8192, 1000));
+    mixer.set_group(a, stems);
+    mixer.play(a);
+    mixer.group_mut(music).unwrap().set_volume(0.5);
+    mixer.group_mut(stems).unwrap().set_volume(0.5);
+    let mut out = vec![0.0; 2 * 200];
+    mixer.render(&mut out);
+    let unit = 8192.0 / i16::MAX as f32;
+    assert!(out[0] > 0.9 * unit);
+    assert!((out[2 * 199] - 0.25 * unit).abs() < 1e-6);
+    //the ramp moves in small steps.
+    for w in out.chunks(2).collect::<Vec<_>>().windows(2) {
+        assert!((w[0][0] - w[1][0]).abs() < 0.02);
+    }
+    assert_eq!(mixer.find_group("stems"), Some(stems));
+}
+
+#[test]
+fn group_mute_and_pause_propagate() {
+    let mut mixer = Mixer::new(8000);
+    let sfx = mixer.add_group("sfx", mixer.master());
+    let a = mixer.add(constant_sound(1, 8000, 8192, 1000));
+    mixer.set_group(a, sfx);
+    mixer.play(a);
+    let mut out = vec![0.0; 2 * 100];
+    mixer.group_mut(mixer.master()).unwrap().set_paused(true);
+    mixer.render(&mut out);
+    assert!(out.iter().all(|s| *s == 0.0));
+    assert_eq!(mixer.sound(a).unwrap().stream().position(), 0);
+    mixer.group_mut(mixer.master()).unwrap().set_paused(false);
+    mixer.group_mut(sfx).unwrap().set_muted(true);
+    mixer.render(&mut out);
+    assert!(out[0] > 0.0);
+    assert_eq!(out[2 * 99], 0.0);
+    assert!(mixer.sound(a).unwrap().stream().position() > 0);
+}
+
+#[test]
+fn sound_effects_run_before_panning() {
+    let mut mixer = Mixer::new(8000);
+    let a = mixer.add(constant_sound(1, 8000, 8192, 4000));
+    mixer.play(a);
+    mixer
+        .sound_mut(a)
+        .unwrap()
+        .effects_mut()
+        .push(crate::effects::Biquad::high_pass(200.0, 0.707));
+    let mut out = vec![0.0; 2 * 4000];
+    mixer.render(&mut out);
+    //a constant signal is blocked by the high-pass once it settles.
+    assert!(out[0] > 0.1);
+    assert!(out[2 * 3000..].iter().all(|s| s.abs() < 1e-3));
+}
+
+#[test]
+fn master_effects_process_the_mix() {
+    let mut mixer = Mixer::new(8000);
+    let a = mixer.add(constant_sound(1, 8000, 8192, 800));
+    mixer.play(a);
+    let mut reverb = crate::effects::Reverb::new();
+    reverb.set_dry(0.0);
+    mixer.effects_mut().push(reverb);
+    let mut out = vec![0.0; 2 * 1600];
+    mixer.render(&mut out);
+    //nothing comes out before the shortest comb, the tail rings on after the sound ended.
+    assert_eq!(out[0], 0.0);
+    assert!(out[2 * 1000..].iter().any(|s| s.abs() > 1e-3));
+}
+
+#[test]
+fn positioned_sounds_follow_the_listener() {
+    use crate::spatial::{Emitter, Vec3};
+    let mut mixer = Mixer::new(8000);
+    let a = mixer.add(constant_sound(1, 8000, 8192, 800));
+    mixer.play(a);
+    mixer
+        .sound_mut(a)
+        .unwrap()
+        .set_emitter(Some(Emitter::at(Vec3::new(4.0, 0.0, 0.0))));
+    let mut out = vec![0.0; 2 * 100];
+    mixer.render(&mut out);
+    let unit = 8192.0 / i16::MAX as f32;
+    assert!(out[0].abs() < 1e-6);
+    assert!((out[1] - unit * 0.25).abs() < 1e-6);
+    //walking up to it and turning around puts it on the left at full level.
+    let listener = mixer.listener_mut();
+    listener.position = Vec3::new(3.5, 0.0, 0.0);
+    listener.forward = Vec3::new(0.0, 0.0, 1.0);
+    mixer.render(&mut out);
+    assert!((out[0] - unit).abs() < 1e-6);
+    assert!(out[1].abs() < 1e-6);
+}
+
+#[test]
+fn approaching_sounds_play_faster() {
+    use crate::spatial::{Emitter, Vec3};
+    let mut mixer = Mixer::new(8000);
+    let a = mixer.add(constant_sound(1, 8000, 8192, 1000));
+    mixer.play(a);
+    let mut emitter = Emitter::at(Vec3::new(0.0, 0.0, -10.0));
+    emitter.velocity = Vec3::new(0.0, 0.0, 34.3 * 5.0);
+    mixer.sound_mut(a).unwrap().set_emitter(Some(emitter));
+    let mut out = vec![0.0; 2 * 1000];
+    mixer.render(&mut out);
+    //doppler of 2 consumes the 1000 frames in half the time.
+    assert!(!mixer.sound(a).unwrap().is_playing());
+    assert_eq!(
+        mixer.sound(a).unwrap().spatial_params().unwrap().doppler,
+        2.0
+    );
+    assert!(out[2 * 490] != 0.0 && out[2 * 510] == 0.0);
+}
+
+#[test]
+fn voice_limit_steals_oldest_with_fade() {
+    let mut mixer = Mixer::new(8000);
+    mixer.voice_limits_mut().set_max_voices(Some(2));
+    mixer.voice_limits_mut().set_fade_ms(10.0);
+    let ids = (0..3)
+        .map(|_| mixer.add(constant_sound(1, 8000, 8192, 1000)))
+        .collect::<Vec<_>>();
+    assert!(mixer.play(ids[0]) && mixer.play(ids[1]));
+    let mut out = vec![0.0; 2 * 10];
+    mixer.render(&mut out);
+    assert!(mixer.play(ids[2]));
+    assert_eq!(mixer.voice_count(), 2);
+    assert!(mixer.sound(ids[0]).unwrap().is_fading_out());
+    //the stolen voice ramps down over 80 frames instead of cutting off.
+    let mut out = vec![0.0; 2 * 100];
+    mixer.render(&mut out);
+    let unit = 8192.0 / i16::MAX as f32 * std::f32::consts::FRAC_1_SQRT_2;
+    assert!(out[0] > 2.9 * unit && out[0] < 3.0 * unit);
+    assert!(out[2 * 40] > 2.4 * unit && out[2 * 40] < 2.6 * unit);
+    assert!((out[2 * 90] - 2.0 * unit).abs() < 1e-5);
+    assert_eq!(mixer.sound(ids[0]).unwrap().state(), PlayState::Stopped);
+}
+
+#[test]
+fn voice_limits_respect_assets_and_priority() {
+    let mut mixer = Mixer::new(8000);
+    mixer.voice_limits_mut().set_asset_limit("step", Some(1));
+    mixer.voice_limits_mut().set_max_voices(Some(2));
+    mixer
+        .voice_limits_mut()
+        .set_policy(StealPolicy::LowestPriority);
+    let add = |mixer: &mut Mixer<_>, asset: Option<&str>, priority| {
+        let id = mixer.add(constant_sound(1, 8000, 8192, 1000));
+        let sound = mixer.sound_mut(id).unwrap();
+        sound.set_asset(asset);
+        sound.set_priority(priority);
+        id
+    };
+    let step_a = add(&mut mixer, Some("step"), 0);
+    let step_b = add(&mut mixer, Some("step"), 0);
+    let music = add(&mut mixer, None, 10);
+    let ui = add(&mut mixer, None, -5);
+    let voice = add(&mut mixer, None, 5);
+    assert!(mixer.play(step_a) && mixer.play(step_b));
+    assert!(mixer.sound(step_a).unwrap().is_fading_out());
+    assert!(mixer.play(music));
+    assert_eq!(mixer.voice_count(), 2);
+    //nothing ranks below the ui sound, so it doesn't get to play.
+    assert!(!mixer.play(ui));
+    assert_eq!(mixer.sound(ui).unwrap().state(), PlayState::Stopped);
+    assert!(mixer.play(voice));
+    assert!(mixer.sound(step_b).unwrap().is_fading_out());
+    assert!(!mixer.sound(music).unwrap().is_fading_out());
+}
+
+#[test]
+fn scheduled_events_land_on_exact_frames() {
+    let mut mixer = Mixer::new(8000);
+    let a = mixer.add(constant_sound(1, 8000, 8192, 1000));
+    mixer.schedule_play(a, 37);
+    mixer.schedule_stop(a, 100);
+    let mut out = vec![0.0; 2 * 128];
+    mixer.render(&mut out);
+    let on = out.chunks_exact(2).map(|f| f[0] != 0.0).collect::<Vec<_>>();
+    assert_eq!(on.iter().position(|o| *o), Some(37));
+    assert_eq!(on.iter().rposition(|o| *o), Some(99));
+    assert_eq!(mixer.time(), 128);
+    assert_eq!(mixer.sound(a).unwrap().state(), PlayState::Stopped);
+}
+
+#[test]
+fn scheduling_is_exact_across_adpcm_blocks() {
+    let file = crate::test_util::adpcm_cks(1, &[[100, 200], [300, 400]]);
+    let mut mixer = Mixer::new(44100);
+    let a = mixer.add(Decoder::new(std::io::Cursor::new(file)).unwrap());
+    mixer.sound_mut(a).unwrap().seek(34);
+    mixer.schedule_play(a, 10);
+    let mut out = vec![0.0; 2 * 16];
+    mixer.render(&mut out);
+    let left = out
+        .chunks_exact(2)
+        .map(|f| (f[0] * std::f32::consts::SQRT_2 * i16::MAX as f32).round() as i16)
+        .collect::<Vec<_>>();
+    assert_eq!(&left[8..15], &[0, 0, 200, 200, 300, 400, 400]);
+}
+
+#[test]
+fn fades_and_crossfades_follow_their_curves() {
+    let mut mixer = Mixer::new(8000);
+    let a = mixer.add(constant_sound(1, 8000, 8192, 1000));
+    let b = mixer.add(constant_sound(1, 8000, 8192, 1000));
+    mixer.fade_in(a, 4, 8, FadeCurve::Linear);
+    let mut out = vec![0.0; 2 * 20];
+    mixer.render(&mut out);
+    let full = out[2 * 19];
+    let ramp = (0..10).map(|i| out[2 * (4 + i)] / full).collect::<Vec<_>>();
+    let expected = [0.0, 0.125, 0.25, 0.375, 0.5, 0.625, 0.75, 0.875, 1.0, 1.0];
+    assert!(ramp.iter().zip(expected).all(|(r, e)| (r - e).abs() < 1e-6));
+
+    mixer.crossfade(a, b, 25, 10, FadeCurve::Exponential);
+    let mut out = vec![0.0; 2 * 20];
+    mixer.render(&mut out);
+    //at the midpoint both sit at -30db; afterwards only b plays, at full level.
+    assert!((out[2 * 10] / full - 2.0 * 10.0_f32.powf(-1.5)).abs() < 1e-5);
+    assert!((out[2 * 19] / full - 1.0).abs() < 1e-6);
+    assert_eq!(mixer.sound(a).unwrap().state(), PlayState::Stopped);
+    assert!(mixer.sound(b).unwrap().is_playing());
+}
+
+#[test]
+fn render_does_not_depend_on_block_size() {
+    let render = |block: usize| {
+        let mut mixer = Mixer::new(22050);
+        let a = mixer.add(constant_sound(2, 16000, 8192, 700));
+        let sound = mixer.sound_mut(a).unwrap();
+        sound.set_speed(1.5);
+        sound
+            .effects_mut()
+            .push(crate::effects::Biquad::low_pass(2000.0, 0.707));
+        mixer.schedule_play(a, 441);
+        mixer.fade_to(a, 600, 300, 0.5, FadeCurve::Exponential);
+        let mut out = vec![0.0; 2 * 2000];
+        for chunk in out.chunks_mut(2 * block) {
+            mixer.render(chunk);
+        }
+        out
+    };
+    let whole = render(2000);
+    assert!(whole[2 * 1000] != 0.0);
+    assert_eq!(whole, render(333));
+    assert_eq!(whole, render(64));
+}
@@ -0,0 +1,104 @@
+//frame-stamped mixer events and the fade envelopes they start.
+use super::SoundId;
+
+//exponential fades move linearly in decibels; silence counts as this level until the last frame.
+static SILENCE_DB: f32 = -60.0;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FadeCurve {
+    Linear,
+    Exponential,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum Action {
+    Play,
+    Stop,
+    //fade from the current gain, or from `from` when given (fade ins).
+    Fade {
+        from: Option<f32>,
+        to: f32,
+        frames: usize,
+        curve: FadeCurve,
+        stop_at_end: bool,
+    },
+}
+
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct Event {
+    pub(crate) frame: u64,
+    pub(crate) sound: SoundId,
+    pub(crate) action: Action,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct Envelope {
+    from: f32,
+    to: f32,
+    frames: usize,
+    done: usize,
+    curve: FadeCurve,
+    pub(crate) stop_at_end: bool,
+}
+
+impl Envelope {
+    pub(crate) fn new(
+        from: f32,
+        to: f32,
+        frames: usize,
+        curve: FadeCurve,
+        stop_at_end: bool,
+    ) -> Self {
+        Self {
+            from,
+            to,
+            frames,
+            done: 0,
+            curve,
+            stop_at_end,
+        }
+    }
+
+    pub(crate) fn is_done(&self) -> bool {
+        self.done >= self.frames
+    }
+
+    pub(crate) fn target(&self) -> f32 {
+        self.to
+    }
+
+    //gain for the next frame; the first frame plays at `from`, the frame after the last at `to`.
+    pub(crate) fn next_gain(&mut self) -> f32 {
+        if self.is_done() {
+            return self.to;
+        }
+        let t = self.done as f32 / self.frames as f32;
+        self.done += 1;
+        match self.curve {
+            FadeCurve::Linear => self.from + (self.to - self.from) * t,
+            FadeCurve::Exponential => {
+                let db = |g: f32| (20.0 * g.max(1e-9).log10()).max(SILENCE_DB);
+                let (a, b) = (db(self.from), db(self.to));
+                let gain = 10.0_f32.powf((a + (b - a) * t) / 20.0);
+                //a fade in from silence still starts silent.
+                if t == 0.0 && self.from == 0.0 {
+                    0.0
+                } else {
+                    gain
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn envelope_curves() {
+    let mut lin = Envelope::new(0.0, 1.0, 4, FadeCurve::Linear, false);
+    let gains = (0..6).map(|_| lin.next_gain()).collect::<Vec<_>>();
+    assert_eq!(gains, [0.0, 0.25, 0.5, 0.75, 1.0, 1.0]);
+    let mut exp = Envelope::new(1.0, 0.0, 2, FadeCurve::Exponential, true);
+    assert_eq!(exp.next_gain(), 1.0);
+    assert!((exp.next_gain() - 10.0_f32.powf(-1.5)).abs() < 1e-6);
+    assert_eq!(exp.next_gain(), 0.0);
+    assert!(exp.is_done() && exp.stop_at_end);
+}
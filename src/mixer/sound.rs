@@ -0,0 +1,318 @@
+use std::io::{Read, Seek};
+
+use super::group::{GainRamp, GroupId};
+use super::schedule::Envelope;
+use crate::decoder::Decoder;
+use crate::effects::{Effect, EffectChain};
+use crate::resample::Resampler;
+use crate::spatial::{self, Emitter, Listener, SpatialParams};
+use crate::stream::SampleStream;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PlayState {
+    Stopped,
+    Playing,
+    Paused,
+}
+
+//one playing instance of a decoder inside a mixer.
+pub struct Sound<R>
+where
+    R: Read + Seek,
+{
+    stream: SampleStream<R>,
+    resampler: Resampler,
+    state: PlayState,
+    volume: f32,
+    pan: f32,
+    speed: f32,
+    pub(crate) group: GroupId,
+    effects: EffectChain,
+    emitter: Option<Emitter>,
+    spatial: Option<SpatialParams>,
+    priority: i32,
+    asset: Option<String>,
+    one_shot: bool,
+    pub(crate) started: u64,
+    fade_out: Option<GainRamp>,
+    envelope: Option<Envelope>,
+    envelope_gain: f32,
+    scratch: Vec<f32>,
+}
+
+impl<R> Sound<R>
+where
+    R: Read + Seek,
+{
+    //volume, pan and loops start from the values stored in the file.
+    pub fn new(decoder: Decoder<R>) -> Self {
+        let stream = SampleStream::new(decoder);
+        let info = stream.sample_info();
+        let volume = info.volume as f32 / u16::MAX as f32;
+        let pan = (info.pan as f32 / i16::MAX as f32).clamp(-1.0, 1.0);
+        let resampler = Resampler::new(stream.channels());
+        Self {
+            stream,
+            resampler,
+            state: PlayState::Stopped,
+            volume,
+            pan,
+            speed: 1.0,
+            group: GroupId(0),
+            effects: EffectChain::new(),
+            emitter: None,
+            spatial: None,
+            priority: 0,
+            asset: None,
+            one_shot: false,
+            started: 0,
+            fade_out: None,
+            envelope: None,
+            envelope_gain: 1.0,
+            scratch: Vec::new(),
+        }
+    }
+
+    pub fn stream(&self) -> &SampleStream<R> {
+        &self.stream
+    }
+
+    //applied at the mixer rate before panning.
+    pub fn effects_mut(&mut self) -> &mut EffectChain {
+        &mut self.effects
+    }
+
+    pub fn group(&self) -> GroupId {
+        self.group
+    }
+
+    pub fn state(&self) -> PlayState {
+        self.state
+    }
+
+    pub fn is_playing(&self) -> bool {
+        self.state == PlayState::Playing
+    }
+
+    pub fn play(&mut self) {
+        self.state = PlayState::Playing;
+        self.fade_out = None;
+    }
+
+    pub fn pause(&mut self) {
+        if self.state == PlayState::Playing {
+            self.state = PlayState::Paused;
+        }
+    }
+
+    //stopping rewinds, so the next play starts from the beginning with the loops restored.
+    pub fn stop(&mut self) {
+        self.state = PlayState::Stopped;
+        self.fade_out = None;
+        self.envelope = None;
+        self.envelope_gain = 1.0;
+        self.stream.seek(0);
+        self.stream.set_loop_count(self.stream.loop_count());
+        self.resampler.reset();
+        self.effects.reset();
+    }
+
+    //moves the read position, in frames of the file.
+    pub fn seek(&mut self, frame: u64) {
+        self.stream.seek(frame);
+        self.resampler.reset();
+    }
+
+    //gain of the scheduled fade envelope, 1.0 when none ran.
+    pub fn envelope_gain(&self) -> f32 {
+        self.envelope_gain
+    }
+
+    pub(crate) fn set_envelope(&mut self, envelope: Envelope) {
+        self.envelope = Some(envelope);
+    }
+
+    //fades to silence over the given frames of mixer output, then stops.
+    pub fn stop_with_fade(&mut self, frames: usize) {
+        if self.state != PlayState::Playing || frames == 0 {
+            self.stop();
+            return;
+        }
+        let mut ramp = GainRamp::new(1.0);
+        ramp.set_target(0.0, frames);
+        self.fade_out = Some(ramp);
+    }
+
+    pub fn is_fading_out(&self) -> bool {
+        self.fade_out.is_some()
+    }
+
+    //higher wins when voices are limited.
+    pub fn priority(&self) -> i32 {
+        self.priority
+    }
+
+    pub fn set_priority(&mut self, priority: i32) {
+        self.priority = priority;
+    }
+
+    //name shared by every instance of the same sound, for per-asset voice limits.
+    pub fn asset(&self) -> Option<&str> {
+        self.asset.as_deref()
+    }
+
+    pub fn set_asset(&mut self, asset: Option<&str>) {
+        self.asset = asset.map(str::to_string);
+    }
+
+    //a one-shot sound is dropped by the mixer once it stops after being played.
+    pub fn is_one_shot(&self) -> bool {
+        self.one_shot
+    }
+
+    pub fn set_one_shot(&mut self, one_shot: bool) {
+        self.one_shot = one_shot;
+    }
+
+    pub fn volume(&self) -> f32 {
+        self.volume
+    }
+
+    pub fn set_volume(&mut self, volume: f32) {
+        self.volume = volume.max(0.0);
+    }
+
+    //-1.0 is left, 1.0 is right.
+    pub fn pan(&self) -> f32 {
+        self.pan
+    }
+
+    pub fn set_pan(&mut self, pan: f32) {
+        self.pan = pan.clamp(-1.0, 1.0);
+    }
+
+    //playback speed; also changes the pitch.
+    pub fn speed(&self) -> f32 {
+        self.speed
+    }
+
+    pub fn set_speed(&mut self, speed: f32) {
+        self.speed = speed.max(0.0);
+    }
+
+    pub fn emitter(&self) -> Option<&Emitter> {
+        self.emitter.as_ref()
+    }
+
+    pub fn emitter_mut(&mut self) -> Option<&mut Emitter> {
+        self.emitter.as_mut()
+    }
+
+    //a positioned sound takes its pan from the listener's point of view instead of set_pan,
+    //its distance gain on top of the volume and a doppler shift on top of the speed.
+    pub fn set_emitter(&mut self, emitter: Option<Emitter>) {
+        self.emitter = emitter;
+        if emitter.is_none() {
+            self.spatial = None;
+        }
+    }
+
+    //what the last rendered block was spatialized with.
+    pub fn spatial_params(&self) -> Option<&SpatialParams> {
+        self.spatial.as_ref()
+    }
+
+    //volume including distance attenuation, without groups.
+    pub(crate) fn audibility(&self) -> f32 {
+        self.volume * self.spatial.map_or(1.0, |p| p.gain)
+    }
+
+    pub(crate) fn update_spatial(&mut self, listener: &Listener) {
+        self.spatial = self.emitter.map(|e| spatial::spatialize(listener, &e));
+    }
+
+    pub fn loop_count(&self) -> i32 {
+        self.stream.loop_count()
+    }
+
+    pub fn set_loop_count(&mut self, loop_count: i32) {
+        self.stream.set_loop_count(loop_count);
+    }
+
+    pub fn set_loop_points(&mut self, start: u64, end: Option<u64>) {
+        self.stream.set_loop_points(start, end);
+    }
+
+    //left and right gains of the current volume and pan.
+    pub(crate) fn gains(&self) -> (f32, f32) {
+        match self.spatial {
+            Some(p) => pan_gains(self.stream.channels(), p.pan, self.volume * p.gain),
+            None => pan_gains(self.stream.channels(), self.pan, self.volume),
+        }
+    }
+
+    //renders frames at the given output rate, unmixed, in the sound's own channel layout.
+    //returns amount of frames written, fewer than asked once the stream is over.
+    pub(crate) fn render_raw(&mut self, out: &mut [f32], sample_rate: u32) -> usize {
+        let doppler = self.spatial.map_or(1.0, |p| p.doppler);
+        let ratio = self.stream.sample_rate() as f64 / sample_rate.max(1) as f64
+            * self.speed as f64
+            * doppler as f64;
+        self.resampler.set_ratio(ratio);
+        let stream = &mut self.stream;
+        self.resampler.process(out, |buf| stream.read(buf))
+    }
+
+    //adds this sound to a stereo interleaved buffer, scaled by a per-frame gain.
+    pub(crate) fn mix_into(&mut self, out: &mut [f32], sample_rate: u32, gain: &[f32]) {
+        if self.state != PlayState::Playing {
+            return;
+        }
+        let ch = self.stream.channels();
+        let frames = out.len() / 2;
+        let mut scratch = std::mem::take(&mut self.scratch);
+        scratch.resize(frames * ch, 0.0);
+        let (left, right) = self.gains();
+        let rendered = self.render_raw(&mut scratch, sample_rate);
+        self.effects
+            .process(&mut scratch[..rendered * ch], ch, sample_rate);
+        for (i, frame) in scratch[..rendered * ch].chunks_exact(ch).enumerate() {
+            let (l, r) = if ch == 1 {
+                (frame[0], frame[0])
+            } else {
+                (frame[0], frame[1])
+            };
+            let mut fade = self.fade_out.as_mut().map_or(1.0, |f| f.next_gain());
+            if let Some(envelope) = self.envelope.as_mut() {
+                self.envelope_gain = envelope.next_gain();
+            }
+            fade *= self.envelope_gain;
+            out[i * 2] += l * left * gain[i] * fade;
+            out[i * 2 + 1] += r * right * gain[i] * fade;
+        }
+        self.scratch = scratch;
+        //stopping resets the effects, so only after they ran on the last frames.
+        let mut ended = rendered < frames;
+        if let Some(envelope) = self.envelope.filter(|e| e.is_done()) {
+            self.envelope = None;
+            self.envelope_gain = envelope.target();
+            ended |= envelope.stop_at_end;
+        }
+        if ended {
+            self.stop();
+        }
+        if self.fade_out.as_ref().is_some_and(|f| !f.is_ramping()) {
+            self.stop();
+        }
+    }
+}
+
+//mono sources use an equal-power pan, stereo sources a balance control.
+pub(crate) fn pan_gains(channels: usize, pan: f32, volume: f32) -> (f32, f32) {
+    if channels == 1 {
+        let angle = (pan + 1.0) * std::f32::consts::FRAC_PI_4;
+        (angle.cos() * volume, angle.sin() * volume)
+    } else {
+        ((1.0 - pan).min(1.0) * volume, (1.0 + pan).min(1.0) * volume)
+    }
+}
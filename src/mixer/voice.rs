@@ -0,0 +1,112 @@
+//caps on concurrently playing sounds and which voice gives way when a cap is hit.
+use std::collections::HashMap;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StealPolicy {
+    Oldest,
+    Quietest,
+    LowestPriority,
+}
+
+//a voice is a sound that is playing and not fading out after being stolen.
+//a new voice can only take the place of one with the same or lower priority.
+#[derive(Clone, Debug)]
+pub struct VoiceLimits {
+    max_voices: Option<usize>,
+    per_asset: HashMap<String, usize>,
+    policy: StealPolicy,
+    fade_ms: f32,
+}
+
+impl Default for VoiceLimits {
+    fn default() -> Self {
+        Self {
+            max_voices: None,
+            per_asset: HashMap::new(),
+            policy: StealPolicy::Oldest,
+            fade_ms: 20.0,
+        }
+    }
+}
+
+impl VoiceLimits {
+    pub fn max_voices(&self) -> Option<usize> {
+        self.max_voices
+    }
+
+    pub fn set_max_voices(&mut self, max_voices: Option<usize>) {
+        self.max_voices = max_voices;
+    }
+
+    pub fn asset_limit(&self, asset: &str) -> Option<usize> {
+        self.per_asset.get(asset).copied()
+    }
+
+    //instances of sounds tagged with this asset name that may play at once.
+    pub fn set_asset_limit(&mut self, asset: &str, limit: Option<usize>) {
+        match limit {
+            Some(limit) => self.per_asset.insert(asset.to_string(), limit),
+            None => self.per_asset.remove(asset),
+        };
+    }
+
+    pub fn policy(&self) -> StealPolicy {
+        self.policy
+    }
+
+    pub fn set_policy(&mut self, policy: StealPolicy) {
+        self.policy = policy;
+    }
+
+    pub fn fade_ms(&self) -> f32 {
+        self.fade_ms
+    }
+
+    //how long a stolen voice takes to fade out.
+    pub fn set_fade_ms(&mut self, fade_ms: f32) {
+        self.fade_ms = fade_ms.max(0.0);
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct Candidate {
+    pub(crate) index: usize,
+    pub(crate) priority: i32,
+    //play order, lower started earlier.
+    pub(crate) started: u64,
+    pub(crate) audibility: f32,
+}
+
+//candidates must already be limited to voices the new one may replace.
+pub(crate) fn choose_victim(policy: StealPolicy, candidates: &[Candidate]) -> Option<usize> {
+    let victim = match policy {
+        StealPolicy::Oldest => candidates.iter().min_by_key(|c| c.started),
+        StealPolicy::Quietest => candidates.iter().min_by(|a, b| {
+            a.audibility
+                .total_cmp(&b.audibility)
+                .then(a.started.cmp(&b.started))
+        }),
+        StealPolicy::LowestPriority => candidates.iter().min_by_key(|c| (c.priority, c.started)),
+    };
+    victim.map(|c| c.index)
+}
+
+#[test]
+fn victims_follow_policy() {
+    let c = |index, priority, started, audibility| Candidate {
+        index,
+        priority,
+        started,
+        audibility,
+    };
+    let voices = [
+        c(0, 5, 3, 0.2),
+        c(1, 1, 7, 0.9),
+        c(2, 1, 5, 0.5),
+        c(3, 9, 1, 0.6),
+    ];
+    assert_eq!(choose_victim(StealPolicy::Oldest, &voices), Some(3));
+    assert_eq!(choose_victim(StealPolicy::Quietest, &voices), Some(0));
+    assert_eq!(choose_victim(StealPolicy::LowestPriority, &voices), Some(2));
+    assert_eq!(choose_victim(StealPolicy::Oldest, &[]), None);
+}
@@ -0,0 +1,184 @@
+//decodes cks data handed over in chunks of any size, such as packets off the network.
+use alloc::vec::Vec;
+
+use crate::decoder_core::block;
+use crate::error::CksError;
+use crate::file_header::{self, HEADER_BYTES};
+use crate::sample::info::SampleInfo;
+
+#[derive(Default)]
+pub struct PushDecoder {
+    pending: Vec<u8>,
+    sample_info: Option<SampleInfo>,
+    blocks_left: Option<u64>,
+    finished: bool,
+    block_buf: Vec<i16>,
+}
+
+impl PushDecoder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn push(&mut self, bytes: &[u8]) {
+        self.pending.extend_from_slice(bytes);
+    }
+
+    //no more data will be pushed. with an unknown block count a trailing partial block is decoded
+    //as far as it goes, otherwise data ending short of the count is a FileRead error.
+    pub fn finish(&mut self) {
+        self.finished = true;
+    }
+
+    //None until the whole header has been pushed and decoded.
+    pub fn sample_info(&self) -> Option<&SampleInfo> {
+        self.sample_info.as_ref()
+    }
+
+    //bytes pushed but not decoded yet.
+    pub fn pending(&self) -> usize {
+        self.pending.len()
+    }
+
+    //decodes every whole block pushed so far into interleaved f32, returns amount of frames decoded.
+    //InsufficientData means more bytes are needed, EoF that the stream is over and FileRead that
+    //it ended after finish before the header's block count was reached.
+    pub fn decode_f32(&mut self, buf: &mut Vec<f32>) -> Result<usize, CksError> {
+        let info = match &self.sample_info {
+            Some(info) => info,
+            None => self.parse_header()?,
+        };
+        let (format, channels) = (info.format.clone(), info.channels as usize);
+        let block_bytes = info.block_bytes.max(1) as usize;
+        let mut blocks = self.pending.len() / block_bytes;
+        if let Some(left) = self.blocks_left {
+            blocks = blocks.min(left as usize);
+        }
+        let mut bytes = blocks * block_bytes;
+        if blocks == 0 {
+            if self.blocks_left == Some(0) {
+                return Err(CksError::EoF);
+            }
+            if !self.finished {
+                return Err(CksError::InsufficientData);
+            }
+            if self.blocks_left.is_some() {
+                return Err(CksError::FileRead);
+            }
+            if self.pending.is_empty() {
+                return Err(CksError::EoF);
+            }
+            bytes = self.pending.len();
+            self.blocks_left = Some(1);
+        }
+        let frames = block::decode_blocks_f32(
+            &format,
+            channels,
+            block_bytes,
+            &self.pending[..bytes],
+            buf,
+            &mut self.block_buf,
+        )?;
+        self.pending.drain(..bytes);
+        if let Some(left) = self.blocks_left.as_mut() {
+            *left -= blocks.max(1) as u64;
+        }
+        Ok(frames)
+    }
+
+    fn parse_header(&mut self) -> Result<&SampleInfo, CksError> {
+        if self.pending.len() < HEADER_BYTES {
+            return Err(if self.finished {
+                CksError::FileRead
+            } else {
+                CksError::InsufficientData
+            });
+        }
+        let info = file_header::parse_header(&self.pending)?;
+        self.pending.drain(..HEADER_BYTES);
+        self.blocks_left = u64::try_from(info.blocks).ok();
+        Ok(self.sample_info.insert(info))
+    }
+}
+
+#[cfg(test)]
+fn decode_in_chunks(file: &[u8], chunk: usize) -> Vec<f32> {
+    let mut decoder = PushDecoder::new();
+    let mut out = Vec::new();
+    let mut buf = Vec::new();
+    let mut chunks = file.chunks(chunk);
+    loop {
+        match decoder.decode_f32(&mut buf) {
+            Ok(_) => out.extend_from_slice(&buf),
+            Err(CksError::InsufficientData) => match chunks.next() {
+                Some(bytes) => decoder.push(bytes),
+                None => decoder.finish(),
+            },
+            Err(CksError::EoF) => return out,
+            Err(e) => panic!("{:?}", e),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn push_decoder_handles_any_chunking() {
+    let file = crate::test_util::adpcm_cks(2, &[[100, 200], [-300, 400], [500, 600], [0, 7]]);
+    let mut expected = Vec::new();
+    let mut buf = Vec::new();
+    let mut reader = crate::decoder::Decoder::new(std::io::Cursor::new(file.clone())).unwrap();
+    while reader.decode_f32(&mut buf).unwrap().is_some() {
+        expected.extend_from_slice(&buf);
+    }
+    //single bytes split the header as well as every block.
+    for chunk in [1, 7, 45, 1000] {
+        assert_eq!(decode_in_chunks(&file, chunk), expected);
+    }
+}
+
+#[test]
+fn push_decoder_reports_missing_data() {
+    let samples = (0..10).map(|i| i * 100).collect::<Vec<i16>>();
+    let mut file = crate::test_util::pcm16_cks(1, 8000, &samples, (0, 0, 0));
+    let mut decoder = PushDecoder::new();
+    let mut buf = Vec::new();
+    decoder.push(&file[..20]);
+    assert!(matches!(
+        decoder.decode_f32(&mut buf),
+        Err(CksError::InsufficientData)
+    ));
+    assert!(decoder.sample_info().is_none());
+    decoder.push(&file[20..47]);
+    assert_eq!(decoder.decode_f32(&mut buf).unwrap(), 1);
+    assert_eq!(decoder.sample_info().unwrap().channels, 1);
+    assert_eq!(decoder.pending(), 1);
+    assert!(matches!(
+        decoder.decode_f32(&mut buf),
+        Err(CksError::InsufficientData)
+    ));
+    //the header promised ten blocks but only one arrived.
+    decoder.finish();
+    assert!(matches!(
+        decoder.decode_f32(&mut buf),
+        Err(CksError::FileRead)
+    ));
+    let mut short = PushDecoder::new();
+    short.push(&file[..file.len() - 2]);
+    short.finish();
+    assert_eq!(short.decode_f32(&mut buf).unwrap(), 9);
+    assert!(matches!(
+        short.decode_f32(&mut buf),
+        Err(CksError::FileRead)
+    ));
+    //with an unknown block count, a trailing partial block is decoded after finish.
+    file[20..24].copy_from_slice(&(-1_i32).to_le_bytes());
+    file.extend_from_slice(&[0x10, 0x20, 0x30]);
+    let decoded = decode_in_chunks(&file, 3);
+    assert_eq!(decoded.len(), 11);
+    let mut bad = PushDecoder::new();
+    bad.push(&[0; 64]);
+    assert!(matches!(
+        bad.decode_f32(&mut buf),
+        Err(CksError::NotCksFile)
+    ));
+}
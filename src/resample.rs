@@ -0,0 +1,154 @@
+//linear interpolating resampler over interleaved f32 frames.
+//input is pulled on demand, so the ratio can change between calls (pitch, doppler).
+
+static CHUNK_FRAMES: usize = 256;
+
+pub struct Resampler {
+    channels: usize,
+    ratio: f64,
+    frac: f64,
+    prev: Vec<f32>,
+    next: Vec<f32>,
+    next_valid: bool,
+    primed: bool,
+    ended: bool,
+    chunk: Vec<f32>,
+    chunk_len: usize,
+    chunk_pos: usize,
+}
+
+impl Resampler {
+    pub fn new(channels: usize) -> Self {
+        let channels = channels.max(1);
+        Self {
+            channels,
+            ratio: 1.0,
+            frac: 0.0,
+            prev: vec![0.0; channels],
+            next: vec![0.0; channels],
+            next_valid: false,
+            primed: false,
+            ended: false,
+            chunk: vec![0.0; CHUNK_FRAMES * channels],
+            chunk_len: 0,
+            chunk_pos: 0,
+        }
+    }
+
+    pub fn channels(&self) -> usize {
+        self.channels
+    }
+
+    //input frames consumed per output frame.
+    pub fn ratio(&self) -> f64 {
+        self.ratio
+    }
+
+    pub fn set_ratio(&mut self, ratio: f64) {
+        self.ratio = ratio.max(0.0);
+    }
+
+    pub fn is_ended(&self) -> bool {
+        self.ended
+    }
+
+    pub fn reset(&mut self) {
+        self.frac = 0.0;
+        self.next_valid = false;
+        self.primed = false;
+        self.ended = false;
+        self.chunk_len = 0;
+        self.chunk_pos = 0;
+    }
+
+    //fills out with interleaved frames pulled through `pull`, which behaves like SampleStream::read.
+    //returns amount of frames written, fewer than asked only when the input has ended.
+    pub fn process<F>(&mut self, out: &mut [f32], mut pull: F) -> usize
+    where
+        F: FnMut(&mut [f32]) -> usize,
+    {
+        let ch = self.channels;
+        if !self.primed {
+            self.primed = true;
+            if !self.pull_frame(&mut pull) {
+                self.ended = true;
+            }
+            std::mem::swap(&mut self.prev, &mut self.next);
+            self.next_valid = self.pull_frame(&mut pull);
+        }
+        let frames = out.len() / ch;
+        for i in 0..frames {
+            if self.ended {
+                return i;
+            }
+            let frac = self.frac as f32;
+            for c in 0..ch {
+                let (p, n) = (self.prev[c], self.next[c]);
+                out[i * ch + c] = p + (n - p) * frac;
+            }
+            self.frac += self.ratio;
+            while self.frac >= 1.0 {
+                self.frac -= 1.0;
+                if !self.next_valid {
+                    self.ended = true;
+                    break;
+                }
+                std::mem::swap(&mut self.prev, &mut self.next);
+                self.next_valid = self.pull_frame(&mut pull);
+            }
+        }
+        frames
+    }
+
+    fn pull_frame<F>(&mut self, pull: &mut F) -> bool
+    where
+        F: FnMut(&mut [f32]) -> usize,
+    {
+        let ch = self.channels;
+        if self.chunk_pos >= self.chunk_len {
+            self.chunk_len = pull(&mut self.chunk);
+            self.chunk_pos = 0;
+            if self.chunk_len == 0 {
+                //hold the last frame instead of fading towards silence.
+                self.next.copy_from_slice(&self.prev);
+                return false;
+            }
+        }
+        let start = self.chunk_pos * ch;
+        self.next.copy_from_slice(&self.chunk[start..start + ch]);
+        self.chunk_pos += 1;
+        true
+    }
+}
+
+#[test]
+fn resampler_keeps_samples_at_unit_ratio() {
+    let input = (0..1000).map(|i| i as f32).collect::<Vec<_>>();
+    let mut pos = 0;
+    let mut pull = |out: &mut [f32]| {
+        let n = std::cmp::min(out.len(), input.len() - pos);
+        out[..n].copy_from_slice(&input[pos..pos + n]);
+        pos += n;
+        n
+    };
+    let mut r = Resampler::new(1);
+    let mut out = vec![0.0; 2000];
+    let n = r.process(&mut out, &mut pull);
+    assert_eq!(&out[..n], &input[..]);
+}
+
+#[test]
+fn resampler_interpolates_at_half_ratio() {
+    let input = [0.0, 1.0, 2.0, 3.0];
+    let mut pos = 0;
+    let mut r = Resampler::new(1);
+    r.set_ratio(0.5);
+    let mut out = vec![0.0; 16];
+    let n = r.process(&mut out, |out: &mut [f32]| {
+        let n = std::cmp::min(out.len(), input.len() - pos);
+        out[..n].copy_from_slice(&input[pos..pos + n]);
+        pos += n;
+        n
+    });
+    assert_eq!(&out[..n], &[0.0, 0.5, 1.0, 1.5, 2.0, 2.5, 3.0, 3.0]);
+}
@@ -0,0 +1,45 @@
+//small seedable generator (splitmix64) so randomized playback is repeatable in tests.
+#[derive(Clone, Debug)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    //uniform in 0..1.
+    pub fn next_f32(&mut self) -> f32 {
+        (self.next_u64() >> 40) as f32 * (1.0 / (1_u64 << 24) as f32)
+    }
+
+    //uniform in min..max; min when the range is empty.
+    pub fn range(&mut self, min: f32, max: f32) -> f32 {
+        if max <= min {
+            min
+        } else {
+            min + (max - min) * self.next_f32()
+        }
+    }
+}
+
+#[test]
+fn rng_is_repeatable() {
+    use alloc::vec::Vec;
+    let mut a = Rng::new(7);
+    let mut b = Rng::new(7);
+    let xs = (0..100).map(|_| a.next_f32()).collect::<Vec<_>>();
+    assert!(xs.iter().all(|x| (0.0..1.0).contains(x)));
+    assert_eq!(xs, (0..100).map(|_| b.next_f32()).collect::<Vec<_>>());
+    assert_ne!(Rng::new(8).next_u64(), Rng::new(7).next_u64());
+    assert_eq!(Rng::new(1).range(2.0, 2.0), 2.0);
+}
@@ -0,0 +1,101 @@
+use std::io::{Read, Seek};
+use std::time::Duration;
+
+use crate::decoder::Decoder;
+use crate::stream::SampleStream;
+
+static BUFFER_FRAMES: usize = 1024;
+
+//plays a cks file through rodio, looping as the file says.
+pub struct CksSource<R>
+where
+    R: Read + Seek,
+{
+    stream: SampleStream<R>,
+    buf: Vec<f32>,
+    buf_len: usize,
+    buf_pos: usize,
+}
+
+impl<R> CksSource<R>
+where
+    R: Read + Seek,
+{
+    pub fn new(decoder: Decoder<R>) -> Self {
+        Self::from_stream(SampleStream::new(decoder))
+    }
+
+    pub fn from_stream(stream: SampleStream<R>) -> Self {
+        let buf = vec![0.; BUFFER_FRAMES * stream.channels()];
+        Self {
+            stream,
+            buf,
+            buf_len: 0,
+            buf_pos: 0,
+        }
+    }
+
+    pub fn into_inner(self) -> SampleStream<R> {
+        self.stream
+    }
+}
+
+impl<R> Iterator for CksSource<R>
+where
+    R: Read + Seek,
+{
+    type Item = f32;
+
+    fn next(&mut self) -> Option<f32> {
+        if self.buf_pos >= self.buf_len {
+            self.buf_len = self.stream.read(&mut self.buf) * self.stream.channels();
+            self.buf_pos = 0;
+            if self.buf_len == 0 {
+                return None;
+            }
+        }
+        let s = self.buf[self.buf_pos];
+        self.buf_pos += 1;
+        Some(s)
+    }
+}
+
+impl<R> rodio::Source for CksSource<R>
+where
+    R: Read + Seek,
+{
+    fn current_span_len(&self) -> Option<usize> {
+        None
+    }
+
+    fn channels(&self) -> u16 {
+        self.stream.channels() as u16
+    }
+
+    fn sample_rate(&self) -> u32 {
+        self.stream.sample_rate()
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        let frames = self.stream.duration_frames()?;
+        Some(Duration::from_secs_f64(
+            frames as f64 / self.stream.sample_rate().max(1) as f64,
+        ))
+    }
+}
+
+#[test]
+fn source_pulls_looped_samples() {
+    use rodio::Source;
+    let samples = (0..8).flat_map(|i| [i, -i]).collect::<Vec<i16>>();
+    let file = crate::test_util::pcm16_cks(2, 8000, &samples, (4, 8, 1));
+    let source = CksSource::new(Decoder::new(std::io::Cursor::new(file)).unwrap());
+    assert_eq!(source.channels(), 2);
+    assert_eq!(source.sample_rate(), 8000);
+    assert_eq!(source.total_duration(), Some(Duration::from_millis(12) / 8));
+    let left = source
+        .step_by(2)
+        .map(|s| (s * i16::MAX as f32).round() as i16)
+        .collect::<Vec<_>>();
+    assert_eq!(left, [0, 1, 2, 3, 4, 5, 6, 7, 4, 5, 6, 7]);
+}
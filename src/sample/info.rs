@@ -1,6 +1,14 @@
-use std::io::{Read, Seek, SeekFrom};
+//size of the sample info following the file header.
+pub(crate) const SAMPLE_INFO_BYTES: usize = 28;
 
-use crate::decoder::DecoderType;
+#[derive(Clone, Debug)]
+pub enum DecoderType {
+    Adpcm,
+    Pcmi8,
+    Pcmi16,
+    Pcmf32,
+    Unknown,
+}
 
 #[derive(Clone, Debug)]
 pub struct SampleInfo {
@@ -20,24 +28,27 @@ pub struct SampleInfo {
 }
 
 impl SampleInfo {
-    pub fn new<R: Read + Seek>(mut reader: R) -> Self {
-        let mut buf = ReaderBuf {
-            buf_4bytes: [0, 0, 0, 0],
-            buf_2bytes: [0, 0],
-            buf_1byte: [0],
-        };
-        let format = read_to_u8(&mut reader, &mut buf);
-        let channels = read_to_u8(&mut reader, &mut buf);
-        let sample_rate = read_to_u16(&mut reader, &mut buf);
-        let blocks = read_to_i32(&mut reader, &mut buf);
-        let block_bytes = read_to_u16(&mut reader, &mut buf);
-        let block_frames = read_to_u16(&mut reader, &mut buf);
-        let volume = read_to_u16(&mut reader, &mut buf);
-        let pan = read_to_i16(&mut reader, &mut buf);
-        let loop_start = read_to_u32(&mut reader, &mut buf);
-        let loop_end = read_to_u32(&mut reader, &mut buf);
-        let loop_count = read_to_i16(&mut reader, &mut buf);
-        let _ = reader.seek(SeekFrom::Current(2));
+    #[cfg(feature = "std")]
+    pub fn new<R: std::io::Read + std::io::Seek>(mut reader: R) -> Self {
+        let mut buf = [0_u8; SAMPLE_INFO_BYTES];
+        let _ = reader.read_exact(&mut buf);
+        Self::from_bytes(&buf)
+    }
+
+    pub fn from_bytes(bytes: &[u8; SAMPLE_INFO_BYTES]) -> Self {
+        let mut reader = &bytes[..];
+        let format = read_to_u8(&mut reader);
+        let channels = read_to_u8(&mut reader);
+        let sample_rate = read_to_u16(&mut reader);
+        let blocks = read_to_i32(&mut reader);
+        let block_bytes = read_to_u16(&mut reader);
+        let block_frames = read_to_u16(&mut reader);
+        let volume = read_to_u16(&mut reader);
+        let pan = read_to_i16(&mut reader);
+        let loop_start = read_to_u32(&mut reader);
+        let loop_end = read_to_u32(&mut reader);
+        let loop_count = read_to_i16(&mut reader);
+        //2 bytes of padding follow.
 
         let format = match format {
             0 => DecoderType::Pcmi16,
@@ -79,58 +90,34 @@ impl SampleInfo {
     }
 }
 
-struct ReaderBuf {
-    buf_4bytes: [u8; 4],
-    buf_2bytes: [u8; 2],
-    buf_1byte: [u8; 1],
+#[inline]
+fn take<const N: usize>(reader: &mut &[u8]) -> [u8; N] {
+    let (bytes, rest) = reader.split_at(N);
+    *reader = rest;
+    bytes.try_into().unwrap()
 }
 
 #[inline]
-fn read_to_u8<R>(reader: &mut R, buf: &mut ReaderBuf) -> u8
-where
-    R: Read + Seek,
-{
-    let mut buf = buf.buf_1byte;
-    let _ = reader.read_exact(&mut buf);
-    buf[0]
+fn read_to_u8(reader: &mut &[u8]) -> u8 {
+    take::<1>(reader)[0]
 }
 
 #[inline]
-fn read_to_u16<R>(reader: &mut R, buf: &mut ReaderBuf) -> u16
-where
-    R: Read + Seek,
-{
-    let mut buf = buf.buf_2bytes;
-    let _ = reader.read_exact(&mut buf);
-    u16::from_le_bytes(buf)
+fn read_to_u16(reader: &mut &[u8]) -> u16 {
+    u16::from_le_bytes(take(reader))
 }
 
 #[inline]
-fn read_to_u32<R>(reader: &mut R, buf: &mut ReaderBuf) -> u32
-where
-    R: Read + Seek,
-{
-    let mut buf = buf.buf_4bytes;
-    let _ = reader.read_exact(&mut buf);
-    u32::from_le_bytes(buf)
+fn read_to_u32(reader: &mut &[u8]) -> u32 {
+    u32::from_le_bytes(take(reader))
 }
 
 #[inline]
-fn read_to_i16<R>(reader: &mut R, buf: &ReaderBuf) -> i16
-where
-    R: Read + Seek,
-{
-    let mut buf = buf.buf_2bytes;
-    let _ = reader.read_exact(&mut buf);
-    i16::from_le_bytes(buf)
+fn read_to_i16(reader: &mut &[u8]) -> i16 {
+    i16::from_le_bytes(take(reader))
 }
 
 #[inline]
-fn read_to_i32<R>(reader: &mut R, buf: &mut ReaderBuf) -> i32
-where
-    R: Read + Seek,
-{
-    let mut buf = buf.buf_4bytes;
-    let _ = reader.read_exact(&mut buf);
-    i32::from_le_bytes(buf)
+fn read_to_i32(reader: &mut &[u8]) -> i32 {
+    i32::from_le_bytes(take(reader))
 }
@@ -0,0 +1,167 @@
+//json descriptions of the effects in crate::effects.
+use serde::Deserialize;
+
+use crate::effects::{
+    Biquad, BitCrusher, Compressor, Distortion, Effect, FilterType, Limiter, Reverb, RingMod,
+};
+
+fn q_default() -> f32 {
+    std::f32::consts::FRAC_1_SQRT_2
+}
+
+fn one() -> f32 {
+    1.0
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case", deny_unknown_fields)]
+pub enum EffectDesc {
+    LowPass {
+        freq: f32,
+        #[serde(default = "q_default")]
+        q: f32,
+    },
+    HighPass {
+        freq: f32,
+        #[serde(default = "q_default")]
+        q: f32,
+    },
+    BandPass {
+        freq: f32,
+        #[serde(default = "q_default")]
+        q: f32,
+    },
+    Notch {
+        freq: f32,
+        #[serde(default = "q_default")]
+        q: f32,
+    },
+    Peaking {
+        freq: f32,
+        #[serde(default = "q_default")]
+        q: f32,
+        gain_db: f32,
+    },
+    LowShelf {
+        freq: f32,
+        #[serde(default = "q_default")]
+        q: f32,
+        gain_db: f32,
+    },
+    HighShelf {
+        freq: f32,
+        #[serde(default = "q_default")]
+        q: f32,
+        gain_db: f32,
+    },
+    Reverb {
+        room_size: Option<f32>,
+        damping: Option<f32>,
+        wet: Option<f32>,
+        dry: Option<f32>,
+        width: Option<f32>,
+        pre_delay_ms: Option<f32>,
+    },
+    BitCrusher {
+        bits: u32,
+        #[serde(default)]
+        hold_ms: f32,
+    },
+    RingMod {
+        freq: f32,
+        #[serde(default = "one")]
+        wet: f32,
+    },
+    Distortion {
+        drive: f32,
+        #[serde(default)]
+        offset: f32,
+        #[serde(default = "one")]
+        wet: f32,
+    },
+    Compressor {
+        threshold_db: f32,
+        ratio: f32,
+        attack_ms: Option<f32>,
+        release_ms: Option<f32>,
+        #[serde(default)]
+        makeup_db: f32,
+    },
+    Limiter {
+        #[serde(default)]
+        ceiling_db: f32,
+        lookahead_ms: Option<f32>,
+        release_ms: Option<f32>,
+    },
+}
+
+impl EffectDesc {
+    pub fn build(&self) -> Box<dyn Effect> {
+        match *self {
+            EffectDesc::LowPass { freq, q } => Box::new(Biquad::low_pass(freq, q)),
+            EffectDesc::HighPass { freq, q } => Box::new(Biquad::high_pass(freq, q)),
+            EffectDesc::BandPass { freq, q } => Box::new(Biquad::band_pass(freq, q)),
+            EffectDesc::Notch { freq, q } => Box::new(Biquad::notch(freq, q)),
+            EffectDesc::Peaking { freq, q, gain_db } => {
+                Box::new(Biquad::new(FilterType::Peaking, freq, q, gain_db))
+            }
+            EffectDesc::LowShelf { freq, q, gain_db } => {
+                Box::new(Biquad::new(FilterType::LowShelf, freq, q, gain_db))
+            }
+            EffectDesc::HighShelf { freq, q, gain_db } => {
+                Box::new(Biquad::new(FilterType::HighShelf, freq, q, gain_db))
+            }
+            EffectDesc::Reverb {
+                room_size,
+                damping,
+                wet,
+                dry,
+                width,
+                pre_delay_ms,
+            } => {
+                let mut reverb = Reverb::new();
+                reverb.set_room_size(room_size.unwrap_or(reverb.room_size()));
+                reverb.set_damping(damping.unwrap_or(reverb.damping()));
+                reverb.set_wet(wet.unwrap_or(reverb.wet()));
+                reverb.set_dry(dry.unwrap_or(reverb.dry()));
+                reverb.set_width(width.unwrap_or(reverb.width()));
+                reverb.set_pre_delay_ms(pre_delay_ms.unwrap_or(reverb.pre_delay_ms()));
+                Box::new(reverb)
+            }
+            EffectDesc::BitCrusher { bits, hold_ms } => Box::new(BitCrusher::new(bits, hold_ms)),
+            EffectDesc::RingMod { freq, wet } => {
+                let mut ring = RingMod::new(freq);
+                ring.set_wet(wet);
+                Box::new(ring)
+            }
+            EffectDesc::Distortion { drive, offset, wet } => {
+                let mut dist = Distortion::new(drive);
+                dist.set_offset(offset);
+                dist.set_wet(wet);
+                Box::new(dist)
+            }
+            EffectDesc::Compressor {
+                threshold_db,
+                ratio,
+                attack_ms,
+                release_ms,
+                makeup_db,
+            } => {
+                let mut comp = Compressor::new(threshold_db, ratio);
+                comp.set_attack_ms(attack_ms.unwrap_or(comp.attack_ms()));
+                comp.set_release_ms(release_ms.unwrap_or(comp.release_ms()));
+                comp.set_makeup_db(makeup_db);
+                Box::new(comp)
+            }
+            EffectDesc::Limiter {
+                ceiling_db,
+                lookahead_ms,
+                release_ms,
+            } => Box::new(Limiter::new(
+                ceiling_db,
+                lookahead_ms.unwrap_or(5.0),
+                release_ms.unwrap_or(50.0),
+            )),
+        }
+    }
+}
@@ -0,0 +1,271 @@
+//offline renders of a json scene description, for regression tests of whole mixes.
+use std::io::{self, Cursor, Write};
+use std::path::Path;
+
+use serde::Deserialize;
+
+use crate::decoder::Decoder;
+use crate::error::CksError;
+use crate::mixer::{Mixer, SoundId};
+
+mod effect;
+pub use effect::EffectDesc;
+
+static BLOCK_FRAMES: usize = 1024;
+//set to rewrite golden files instead of comparing against them.
+pub static BLESS_ENV: &str = "CKS_BLESS";
+
+#[derive(Debug)]
+pub enum SceneError {
+    Io(io::Error),
+    Json(serde_json::Error),
+    Decode(String, CksError),
+    //no frame count given and some sound loops forever.
+    Unbounded,
+    //the output length overflows or doesn't fit in memory.
+    TooLong,
+    //first differing output frame against a golden file.
+    GoldenMismatch(usize),
+}
+
+impl From<io::Error> for SceneError {
+    fn from(e: io::Error) -> Self {
+        SceneError::Io(e)
+    }
+}
+
+impl From<serde_json::Error> for SceneError {
+    fn from(e: serde_json::Error) -> Self {
+        SceneError::Json(e)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Scene {
+    pub sample_rate: u32,
+    //output length; when left out the render ends with the last sound.
+    #[serde(default)]
+    pub frames: Option<u64>,
+    //master bus effects.
+    #[serde(default)]
+    pub effects: Vec<EffectDesc>,
+    pub sounds: Vec<SceneSound>,
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SceneSound {
+    //relative to the base directory passed to render.
+    pub file: String,
+    //output frame the sound starts on.
+    #[serde(default)]
+    pub start: u64,
+    //multiplies the volume stored in the file.
+    #[serde(default)]
+    pub gain: Option<f32>,
+    //replaces the pan stored in the file.
+    #[serde(default)]
+    pub pan: Option<f32>,
+    //replaces the loop count stored in the file.
+    #[serde(default)]
+    pub loop_count: Option<i32>,
+    #[serde(default)]
+    pub speed: Option<f32>,
+    #[serde(default)]
+    pub effects: Vec<EffectDesc>,
+}
+
+impl Scene {
+    pub fn from_json(json: &str) -> Result<Self, SceneError> {
+        Ok(serde_json::from_str(json)?)
+    }
+
+    //stereo interleaved f32 at the scene rate, files read from base_dir.
+    pub fn render(&self, base_dir: &Path) -> Result<Vec<f32>, SceneError> {
+        self.render_with(|file| std::fs::read(base_dir.join(file)))
+    }
+
+    pub fn render_to_wav<W: Write>(&self, base_dir: &Path, writer: W) -> Result<(), SceneError> {
+        let samples = self.render(base_dir)?;
+        crate::wav::write_wav_i16(writer, &samples, 2, self.sample_rate)?;
+        Ok(())
+    }
+
+    //same as render with file bytes coming from `load`.
+    pub fn render_with<F>(&self, mut load: F) -> Result<Vec<f32>, SceneError>
+    where
+        F: FnMut(&str) -> io::Result<Vec<u8>>,
+    {
+        let mut mixer = Mixer::new(self.sample_rate);
+        for desc in self.effects.iter() {
+            mixer.effects_mut().push(desc.build());
+        }
+        let mut end = 0;
+        for desc in self.sounds.iter() {
+            let decoder = Decoder::new(Cursor::new(load(&desc.file)?))
+                .map_err(|e| SceneError::Decode(desc.file.clone(), e))?;
+            let id = mixer.add(decoder);
+            let sound = mixer.sound_mut(id).unwrap();
+            if let Some(gain) = desc.gain {
+                sound.set_volume(sound.volume() * gain);
+            }
+            if let Some(pan) = desc.pan {
+                sound.set_pan(pan);
+            }
+            if let Some(loop_count) = desc.loop_count {
+                sound.set_loop_count(loop_count);
+            }
+            if let Some(speed) = desc.speed {
+                sound.set_speed(speed);
+            }
+            for effect in desc.effects.iter() {
+                sound.effects_mut().push(effect.build());
+            }
+            if self.frames.is_none() {
+                let stream = sound.stream();
+                let frames = stream.duration_frames().ok_or(SceneError::Unbounded)?;
+                let rate = self.sample_rate as f64
+                    / (stream.sample_rate().max(1) as f64 * sound.speed().max(1e-6) as f64);
+                let len = (frames as f64 * rate).ceil() as u64;
+                end = end.max(desc.start.checked_add(len).ok_or(SceneError::TooLong)?);
+            }
+            mixer.schedule_play(id, desc.start);
+        }
+        let len = usize::try_from(self.frames.unwrap_or(end))
+            .ok()
+            .and_then(|frames| frames.checked_mul(2))
+            .ok_or(SceneError::TooLong)?;
+        let mut out = Vec::new();
+        out.try_reserve_exact(len).or(Err(SceneError::TooLong))?;
+        out.resize(len, 0.0);
+        for block in out.chunks_mut(BLOCK_FRAMES * 2) {
+            mixer.render(block);
+        }
+        Ok(out)
+    }
+}
+
+//compares a stereo render, as 16-bit wav, with the golden file at path.
+//with CKS_BLESS set in the environment the golden file is written instead.
+pub fn check_golden(path: &Path, samples: &[f32], sample_rate: u32) -> Result<(), SceneError> {
+    let mut wav = Vec::new();
+    crate::wav::write_wav_i16(&mut wav, samples, 2, sample_rate)?;
+    if std::env::var_os(BLESS_ENV).is_some() {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        std::fs::write(path, &wav)?;
+        return Ok(());
+    }
+    let golden = std::fs::read(path)?;
+    if golden == wav {
+        return Ok(());
+    }
+    //44 byte header, 4 bytes a frame.
+    let frame = golden
+        .iter()
+        .zip(wav.iter())
+        .position(|(a, b)| a != b)
+        .unwrap_or(golden.len().min(wav.len()));
+    Err(SceneError::GoldenMismatch(frame.saturating_sub(44) / 4))
+}
+
+#[cfg(test)]
+fn test_files(file: &str) -> io::Result<Vec<u8>> {
+    let ramp = (0..400).map(|i| (i * 40 - 8000) as i16).collect::<Vec<_>>();
+    match file {
+        "ramp.cks" => Ok(crate::test_util::pcm16_cks(1, 8000, &ramp, (100, 300, 1))),
+        "tone.cks" => {
+            let tone = crate::effects::sine(440.0, 16000, 800, 2)
+                .iter()
+                .map(|s| (s * 12000.0) as i16)
+                .collect::<Vec<_>>();
+            Ok(crate::test_util::pcm16_cks(2, 16000, &tone, (0, 0, 0)))
+        }
+        _ => Err(io::ErrorKind::NotFound.into()),
+    }
+}
+
+#[test]
+fn scene_starts_sounds_on_their_frame() {
+    let scene = Scene::from_json(
+        r#"{"sample_rate": 8000, "sounds": [
+            {"file": "ramp.cks", "start": 1500, "loop_count": 0, "pan": -1.0}
+        ]}"#,
+    )
+    .unwrap();
+    let out = scene.render_with(test_files).unwrap();
+    assert_eq!(out.len(), 2 * 1900);
+    assert!(out[..2 * 1500].iter().all(|s| *s == 0.0));
+    assert_eq!(out[2 * 1500], -8000.0 / i16::MAX as f32);
+    assert_eq!(out[2 * 1500 + 1], 0.0);
+}
+
+#[test]
+fn scene_reports_bad_input() {
+    let scene = Scene::from_json(
+        r#"{"sample_rate": 8000, "sounds": [{"file": "ramp.cks", "loop_count": -1}]}"#,
+    )
+    .unwrap();
+    assert!(matches!(
+        scene.render_with(test_files),
+        Err(SceneError::Unbounded)
+    ));
+    let scene =
+        Scene::from_json(r#"{"sample_rate": 8000, "sounds": [{"file": "none.cks"}]}"#).unwrap();
+    assert!(matches!(
+        scene.render_with(test_files),
+        Err(SceneError::Io(_))
+    ));
+    let scene = Scene::from_json(&format!(
+        r#"{{"sample_rate": 8000, "sounds": [{{"file": "ramp.cks", "start": {}}}]}}"#,
+        u64::MAX
+    ))
+    .unwrap();
+    assert!(matches!(
+        scene.render_with(test_files),
+        Err(SceneError::TooLong)
+    ));
+    let scene = Scene::from_json(&format!(
+        r#"{{"sample_rate": 8000, "frames": {}, "sounds": []}}"#,
+        u64::MAX
+    ))
+    .unwrap();
+    assert!(matches!(
+        scene.render_with(test_files),
+        Err(SceneError::TooLong)
+    ));
+    assert!(matches!(
+        Scene::from_json(r#"{"sample_rate": 8000, "sounds": [], "extra": 1}"#),
+        Err(SceneError::Json(_))
+    ));
+}
+
+#[test]
+fn scene_matches_golden_mix() {
+    let scene = Scene::from_json(
+        r#"{
+            "sample_rate": 22050,
+            "effects": [
+                {"type": "reverb", "room_size": 0.6, "wet": 0.2, "pre_delay_ms": 10},
+                {"type": "limiter", "ceiling_db": -1.0}
+            ],
+            "sounds": [
+                {"file": "ramp.cks", "start": 0, "gain": 0.8, "pan": -0.5},
+                {"file": "tone.cks", "start": 441, "speed": 1.5, "effects": [
+                    {"type": "low_pass", "freq": 2000},
+                    {"type": "bit_crusher", "bits": 6, "hold_ms": 0.2}
+                ]},
+                {"file": "ramp.cks", "start": 900, "loop_count": 2, "pan": 0.7, "effects": [
+                    {"type": "ring_mod", "freq": 300, "wet": 0.5}
+                ]}
+            ]
+        }"#,
+    )
+    .unwrap();
+    let first = scene.render_with(test_files).unwrap();
+    assert_eq!(first, scene.render_with(test_files).unwrap());
+    let golden = Path::new(env!("CARGO_MANIFEST_DIR")).join("src/scene/golden/basic_mix.wav");
+    check_golden(&golden, &first, scene.sample_rate).unwrap();
+}
@@ -0,0 +1,388 @@
+//plays decoders back to back without gaps, optionally overlapping them with an equal-power crossfade.
+use std::io::{Read, Seek};
+
+use crate::decoder::Decoder;
+use crate::resample::Resampler;
+use crate::stream::SampleStream;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Transition {
+    //segment indices; from is None for the first segment, to is None once the last one ended.
+    pub from: Option<usize>,
+    pub to: Option<usize>,
+    //output frame on which `to` starts.
+    pub frame: u64,
+}
+
+struct Segment<R>
+where
+    R: Read + Seek,
+{
+    stream: SampleStream<R>,
+    resampler: Resampler,
+    //frames of overlap with the end of the segment before, at the output rate.
+    crossfade: usize,
+    rendered: u64,
+    scratch: Vec<f32>,
+}
+
+impl<R> Segment<R>
+where
+    R: Read + Seek,
+{
+    //output frames this segment lasts, None when it loops forever.
+    fn length(&self, sample_rate: u32) -> Option<u64> {
+        let frames = self.stream.duration_frames()?;
+        let ratio = self.stream.sample_rate() as f64 / sample_rate.max(1) as f64;
+        Some((frames as f64 / ratio).ceil() as u64)
+    }
+
+    //renders into out in the sequencer's layout, returns frames written.
+    fn render(&mut self, out: &mut [f32], channels: usize, sample_rate: u32) -> usize {
+        let ch = self.stream.channels();
+        let frames = out.len() / channels;
+        self.scratch.resize(frames * ch, 0.0);
+        self.resampler
+            .set_ratio(self.stream.sample_rate() as f64 / sample_rate.max(1) as f64);
+        let stream = &mut self.stream;
+        let got = self
+            .resampler
+            .process(&mut self.scratch, |buf| stream.read(buf));
+        for (src, dst) in self.scratch[..got * ch]
+            .chunks_exact(ch)
+            .zip(out.chunks_exact_mut(channels))
+        {
+            if ch == channels {
+                dst.copy_from_slice(src);
+            } else if ch == 1 {
+                dst.iter_mut().for_each(|d| *d = src[0]);
+            } else if channels == 1 {
+                dst[0] = src.iter().sum::<f32>() / ch as f32;
+            } else {
+                let n = std::cmp::min(ch, channels);
+                dst[..n].copy_from_slice(&src[..n]);
+                dst[n..].iter_mut().for_each(|d| *d = 0.0);
+            }
+        }
+        self.rendered += got as u64;
+        got
+    }
+}
+
+struct Fade {
+    from: usize,
+    done: usize,
+    total: usize,
+}
+
+pub struct Sequencer<R>
+where
+    R: Read + Seek,
+{
+    channels: usize,
+    sample_rate: u32,
+    segments: Vec<Option<Segment<R>>>,
+    current: Option<usize>,
+    started: bool,
+    fade: Option<Fade>,
+    position: u64,
+    fade_buf: Vec<f32>,
+    on_transition: Option<Box<dyn FnMut(Transition)>>,
+}
+
+impl<R> Sequencer<R>
+where
+    R: Read + Seek,
+{
+    //segments are resampled and channel-mapped to this output format.
+    pub fn new(channels: usize, sample_rate: u32) -> Self {
+        Self {
+            channels: channels.max(1),
+            sample_rate,
+            segments: Vec::new(),
+            current: None,
+            started: false,
+            fade: None,
+            position: 0,
+            fade_buf: Vec::new(),
+            on_transition: None,
+        }
+    }
+
+    pub fn channels(&self) -> usize {
+        self.channels
+    }
+
+    pub fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    //appends a segment that loops as stored in its file. returns its index.
+    pub fn push(&mut self, decoder: Decoder<R>) -> usize {
+        self.push_with(decoder, None, 0)
+    }
+
+    //loop_count replaces the file's when given; crossfade overlaps the start of this segment with
+    //the end of the previous one, in output frames.
+    pub fn push_with(
+        &mut self,
+        decoder: Decoder<R>,
+        loop_count: Option<i32>,
+        crossfade: usize,
+    ) -> usize {
+        let mut stream = SampleStream::new(decoder);
+        if let Some(loop_count) = loop_count {
+            stream.set_loop_count(loop_count);
+        }
+        let resampler = Resampler::new(stream.channels());
+        self.segments.push(Some(Segment {
+            stream,
+            resampler,
+            crossfade,
+            rendered: 0,
+            scratch: Vec::new(),
+        }));
+        self.segments.len() - 1
+    }
+
+    pub fn len(&self) -> usize {
+        self.segments.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.segments.is_empty()
+    }
+
+    //segment playing now; during a crossfade the one fading in.
+    pub fn current(&self) -> Option<usize> {
+        self.current
+    }
+
+    //output frames produced so far.
+    pub fn position(&self) -> u64 {
+        self.position
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.started && self.current.is_none()
+    }
+
+    //called from read whenever a segment starts or the sequence ends.
+    pub fn set_on_transition<F>(&mut self, callback: F)
+    where
+        F: FnMut(Transition) + 'static,
+    {
+        self.on_transition = Some(Box::new(callback));
+    }
+
+    //moves on to the next segment now, using its crossfade. the way out of endless loops.
+    pub fn skip(&mut self) {
+        if !self.started {
+            self.start();
+        }
+        if self.current.is_some() {
+            self.advance(true);
+        }
+    }
+
+    //fills out with interleaved frames, returns amount of frames written. 0 means the end.
+    pub fn read(&mut self, out: &mut [f32]) -> usize {
+        if !self.started {
+            self.start();
+        }
+        let ch = self.channels;
+        let wanted = out.len() / ch;
+        let mut written = 0;
+        while written < wanted {
+            let Some(cur) = self.current else {
+                break;
+            };
+            let until = self.frames_until_crossfade(cur);
+            if until == Some(0) {
+                self.advance(true);
+                continue;
+            }
+            let n = std::cmp::min(wanted - written, until.unwrap_or(usize::MAX));
+            let dst = &mut out[written * ch..(written + n) * ch];
+            let got = self.render_segment(cur, dst);
+            if self.fade.is_some() {
+                self.mix_fade(dst, got);
+            }
+            written += got;
+            self.position += got as u64;
+            if got < n {
+                self.advance(false);
+            }
+        }
+        written
+    }
+
+    fn start(&mut self) {
+        self.started = true;
+        if !self.segments.is_empty() {
+            self.current = Some(0);
+            self.notify(None, Some(0));
+        }
+    }
+
+    fn render_segment(&mut self, index: usize, out: &mut [f32]) -> usize {
+        match self.segments[index].as_mut() {
+            Some(segment) => segment.render(out, self.channels, self.sample_rate),
+            None => 0,
+        }
+    }
+
+    fn frames_until_crossfade(&self, cur: usize) -> Option<usize> {
+        if self.fade.is_some() {
+            return None;
+        }
+        let next = self.segments.get(cur + 1)?.as_ref()?;
+        if next.crossfade == 0 {
+            return None;
+        }
+        let segment = self.segments[cur].as_ref()?;
+        let start = segment
+            .length(self.sample_rate)?
+            .saturating_sub(next.crossfade as u64);
+        Some(start.saturating_sub(segment.rendered) as usize)
+    }
+
+    //mixes the segment fading out under the first `got` frames of dst.
+    fn mix_fade(&mut self, dst: &mut [f32], got: usize) {
+        let ch = self.channels;
+        let Some(fade) = self.fade.as_mut() else {
+            return;
+        };
+        let mut buf = std::mem::take(&mut self.fade_buf);
+        buf.resize(got * ch, 0.0);
+        let from = fade.from;
+        let outgoing = match self.segments[from].as_mut() {
+            Some(segment) => segment.render(&mut buf, ch, self.sample_rate),
+            None => 0,
+        };
+        let fade = self.fade.as_mut().unwrap();
+        for (i, frame) in dst[..got * ch].chunks_exact_mut(ch).enumerate() {
+            let t = ((fade.done + i) as f32 / fade.total as f32).min(1.0);
+            let (fade_in, fade_out) = (t * std::f32::consts::FRAC_PI_2).sin_cos();
+            for (c, s) in frame.iter_mut().enumerate() {
+                let old = if i < outgoing { buf[i * ch + c] } else { 0.0 };
+                *s = *s * fade_in + old * fade_out;
+            }
+        }
+        fade.done += got;
+        if fade.done >= fade.total || outgoing < got {
+            self.segments[from] = None;
+            self.fade = None;
+        }
+        self.fade_buf = buf;
+    }
+
+    //crossfade uses the next segment's overlap; otherwise the current one is dropped right here.
+    fn advance(&mut self, crossfade: bool) {
+        let Some(cur) = self.current else {
+            return;
+        };
+        //a fade still running is cut short.
+        if let Some(fade) = self.fade.take() {
+            self.segments[fade.from] = None;
+        }
+        let next = cur + 1;
+        let overlap = self
+            .segments
+            .get(next)
+            .and_then(|s| s.as_ref())
+            .map_or(0, |s| s.crossfade);
+        if crossfade && overlap > 0 {
+            self.fade = Some(Fade {
+                from: cur,
+                done: 0,
+                total: overlap,
+            });
+        } else {
+            self.segments[cur] = None;
+        }
+        self.current = if next < self.segments.len() {
+            Some(next)
+        } else {
+            None
+        };
+        self.notify(Some(cur), self.current);
+    }
+
+    fn notify(&mut self, from: Option<usize>, to: Option<usize>) {
+        let frame = self.position;
+        if let Some(callback) = self.on_transition.as_mut() {
+            callback(Transition { from, to, frame });
+        }
+    }
+}
+
+#[cfg(test)]
+fn segment(samples: &[i16], loops: (u32, u32, i16)) -> Decoder<std::io::Cursor<Vec<u8>>> {
+    let file = crate::test_util::pcm16_cks(1, 8000, samples, loops);
+    Decoder::new(std::io::Cursor::new(file)).unwrap()
+}
+
+#[cfg(test)]
+fn read_all<R: Read + Seek>(seq: &mut Sequencer<R>, chunk: usize) -> Vec<i16> {
+    let mut all = Vec::new();
+    let mut buf = vec![0.0; chunk * seq.channels()];
+    loop {
+        let n = seq.read(&mut buf);
+        if n == 0 {
+            return all;
+        }
+        all.extend(
+            buf[..n * seq.channels()]
+                .iter()
+                .map(|s| (s * i16::MAX as f32).round() as i16),
+        );
+    }
+}
+
+#[test]
+fn sequencer_is_gapless_and_reports_transitions() {
+    let transitions = std::rc::Rc::new(std::cell::RefCell::new(Vec::new()));
+    let mut seq = Sequencer::new(1, 8000);
+    seq.push(segment(&(1..=50).collect::<Vec<_>>(), (0, 0, 0)));
+    seq.push(segment(&(51..=100).collect::<Vec<_>>(), (0, 0, 0)));
+    let log = transitions.clone();
+    seq.set_on_transition(move |t| log.borrow_mut().push(t));
+    assert_eq!(read_all(&mut seq, 7), (1..=100).collect::<Vec<_>>());
+    assert!(seq.is_finished());
+    let t = |from, to, frame| Transition { from, to, frame };
+    assert_eq!(
+        *transitions.borrow(),
+        [
+            t(None, Some(0), 0),
+            t(Some(0), Some(1), 50),
+            t(Some(1), None, 100)
+        ]
+    );
+}
+
+#[test]
+fn sequencer_follows_segment_loops() {
+    let mut seq = Sequencer::new(1, 8000);
+    seq.push(segment(&[1, 2, 3, 4], (1, 3, 1)));
+    seq.push_with(segment(&[5, 6, 7], (0, 0, 3)), Some(1), 0);
+    assert_eq!(read_all(&mut seq, 64), [1, 2, 3, 2, 3, 4, 5, 6, 7, 5, 6, 7]);
+}
+
+#[test]
+fn sequencer_crossfades_and_skips_endless_loops() {
+    let mut seq = Sequencer::new(2, 16000);
+    seq.push_with(segment(&[1000; 20], (0, 0, 0)), Some(-1), 0);
+    seq.push_with(segment(&[2000; 40], (0, 0, 0)), None, 8);
+    //mono at 8khz comes out as stereo at 16khz, and loops until skipped.
+    let mut buf = vec![0.0; 2 * 100];
+    assert_eq!(seq.read(&mut buf), 100);
+    seq.skip();
+    let rest = read_all(&mut seq, 5);
+    assert_eq!(rest.len(), 2 * 80);
+    //equal power: halfway through both are at cos(pi/4).
+    let mid = (1000.0 + 2000.0) * std::f32::consts::FRAC_1_SQRT_2;
+    assert!((rest[2 * 4] as f32 - mid).abs() <= 1.0);
+    assert_eq!(rest[2 * 4], rest[2 * 4 + 1]);
+    assert_eq!(rest[2 * 10], 2000);
+}
@@ -0,0 +1,185 @@
+//decodes straight out of bytes already in memory, without copying them through a reader.
+use std::sync::Arc;
+
+use crate::decoder_core::block;
+use crate::error::CksError;
+use crate::file_header::{self, HEADER_BYTES};
+use crate::sample::info::SampleInfo;
+
+#[derive(Clone)]
+enum Data {
+    Shared(Arc<[u8]>),
+    #[cfg(feature = "mmap")]
+    Mapped(Arc<memmap2::Mmap>),
+}
+
+impl Data {
+    fn bytes(&self) -> &[u8] {
+        match self {
+            Data::Shared(b) => b,
+            #[cfg(feature = "mmap")]
+            Data::Mapped(m) => m,
+        }
+    }
+}
+
+//cloning shares the bytes, so every clone is its own cheap playback cursor.
+#[derive(Clone)]
+pub struct SliceDecoder {
+    data: Data,
+    sample_info: SampleInfo,
+    position: usize,
+    block_buf: Vec<i16>,
+}
+
+impl SliceDecoder {
+    pub fn new(bytes: impl Into<Arc<[u8]>>) -> Result<Self, CksError> {
+        Self::with_data(Data::Shared(bytes.into()))
+    }
+
+    //the file must not be changed or truncated while any decoder on it is alive.
+    #[cfg(feature = "mmap")]
+    pub fn open_mmap(path: impl AsRef<std::path::Path>) -> Result<Self, CksError> {
+        let file = std::fs::File::open(path).or(Err(CksError::FileRead))?;
+        let map = unsafe { memmap2::Mmap::map(&file) }.or(Err(CksError::FileRead))?;
+        Self::with_data(Data::Mapped(Arc::new(map)))
+    }
+
+    fn with_data(data: Data) -> Result<Self, CksError> {
+        let sample_info = file_header::parse_header(data.bytes())?;
+        Ok(Self {
+            data,
+            sample_info,
+            position: HEADER_BYTES,
+            block_buf: Vec::new(),
+        })
+    }
+
+    pub fn sample_info(&self) -> SampleInfo {
+        self.sample_info.clone()
+    }
+
+    //blocks held in the data, counting a trailing partial block.
+    pub fn blocks(&self) -> usize {
+        let bytes = self.data.bytes().len() - HEADER_BYTES;
+        bytes.div_ceil(self.sample_info.block_bytes.max(1) as usize)
+    }
+
+    //decodes up to the given blocks into interleaved f32 whatever the stored format is.
+    //returns amount of frames decoded, None at the end of the data.
+    pub fn decode_f32(
+        &mut self,
+        buf: &mut Vec<f32>,
+        blocks: usize,
+    ) -> Result<Option<usize>, CksError> {
+        let block_bytes = self.sample_info.block_bytes as usize;
+        let bytes = self.data.bytes();
+        let end = std::cmp::min(
+            self.position
+                .saturating_add(blocks.saturating_mul(block_bytes)),
+            bytes.len(),
+        );
+        if end <= self.position {
+            return Ok(None);
+        }
+        let frames = block::decode_blocks_f32(
+            &self.sample_info.format,
+            self.sample_info.channels as usize,
+            block_bytes,
+            &bytes[self.position..end],
+            buf,
+            &mut self.block_buf,
+        )?;
+        self.position = end;
+        Ok(Some(frames))
+    }
+
+    //block starts with 0.
+    pub fn set_block_pos(&mut self, block: usize) {
+        let pos = HEADER_BYTES
+            .saturating_add(block.saturating_mul(self.sample_info.block_bytes as usize));
+        self.position = pos.min(self.data.bytes().len());
+    }
+
+    pub fn block_pos(&self) -> usize {
+        (self.position - HEADER_BYTES) / self.sample_info.block_bytes.max(1) as usize
+    }
+}
+
+#[test]
+fn slice_decoder_matches_reader_decoder() {
+    let file = crate::test_util::adpcm_cks(1, &[[100, 200], [-300, 400], [500, 600]]);
+    let mut expected = Vec::new();
+    let mut buf = Vec::new();
+    let mut reader = crate::decoder::Decoder::new(std::io::Cursor::new(file.clone())).unwrap();
+    while reader.decode_f32(&mut buf).unwrap().is_some() {
+        expected.extend_from_slice(&buf);
+    }
+    let mut decoder = SliceDecoder::new(file).unwrap();
+    assert_eq!(decoder.blocks(), 3);
+    assert_eq!(
+        decoder.decode_f32(&mut buf, 10).unwrap(),
+        Some(expected.len())
+    );
+    assert_eq!(buf, expected);
+    assert_eq!(decoder.decode_f32(&mut buf, 1).unwrap(), None);
+    assert!(matches!(
+        SliceDecoder::new(vec![0_u8; 64]),
+        Err(CksError::NotCksFile)
+    ));
+    assert!(SliceDecoder::new(vec![0_u8; 8]).is_err());
+}
+
+#[test]
+fn slice_decoder_clamps_huge_block_counts() {
+    let file = crate::test_util::adpcm_cks(1, &[[100, 200], [-300, 400]]);
+    let mut decoder = SliceDecoder::new(file).unwrap();
+    let mut buf = Vec::new();
+    decoder.set_block_pos(usize::MAX);
+    assert_eq!(decoder.decode_f32(&mut buf, 1).unwrap(), None);
+    decoder.set_block_pos(1);
+    assert_eq!(decoder.decode_f32(&mut buf, usize::MAX).unwrap(), Some(36));
+    assert_eq!(buf[0], -300.0 / i16::MAX as f32);
+
+    let mut corrupt = crate::test_util::adpcm_cks(1, &[[100, 200]]);
+    corrupt[HEADER_BYTES] = 9;
+    let mut decoder = SliceDecoder::new(corrupt).unwrap();
+    assert!(matches!(
+        decoder.decode_f32(&mut buf, 1),
+        Err(CksError::InvalidBlock)
+    ));
+}
+
+#[test]
+fn slice_decoder_clones_are_independent_cursors() {
+    let samples = (0..20).map(|i| i * 100).collect::<Vec<i16>>();
+    let mut a =
+        SliceDecoder::new(crate::test_util::pcm16_cks(1, 8000, &samples, (0, 0, 0))).unwrap();
+    let mut buf = Vec::new();
+    a.decode_f32(&mut buf, 5).unwrap();
+    let mut b = a.clone();
+    b.set_block_pos(0);
+    a.decode_f32(&mut buf, 1).unwrap();
+    assert_eq!(buf, [500.0 / i16::MAX as f32]);
+    b.decode_f32(&mut buf, 1).unwrap();
+    assert_eq!(buf, [0.0]);
+    assert_eq!((a.block_pos(), b.block_pos()), (6, 1));
+}
+
+#[cfg(feature = "mmap")]
+#[test]
+fn slice_decoder_maps_files() {
+    let path = std::env::temp_dir().join(format!("cks_mmap_{}.cks", std::process::id()));
+    let samples = [1000_i16, -1000, 2000, -2000];
+    std::fs::write(
+        &path,
+        crate::test_util::pcm16_cks(2, 8000, &samples, (0, 0, 0)),
+    )
+    .unwrap();
+    let mut decoder = SliceDecoder::open_mmap(&path).unwrap();
+    let mut buf = Vec::new();
+    assert_eq!(decoder.decode_f32(&mut buf, 2).unwrap(), Some(2));
+    assert_eq!(buf[2], 2000.0 / i16::MAX as f32);
+    drop(decoder);
+    std::fs::remove_file(path).unwrap();
+}
@@ -0,0 +1,230 @@
+//decoding from sources read front to back: stdin, pipes, sockets, decompressors.
+//ByteSource is the minimal input the decoding core needs, so this also runs without std.
+use alloc::vec::Vec;
+
+use crate::decoder_core::block;
+use crate::error::CksError;
+use crate::file_header::{self, HEADER_BYTES};
+use crate::sample::info::SampleInfo;
+
+pub trait ByteSource {
+    //reads up to buf.len() bytes, returns amount read; 0 only at the end of the data.
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize, CksError>;
+}
+
+#[cfg(feature = "std")]
+impl<R: std::io::Read> ByteSource for R {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize, CksError> {
+        loop {
+            match std::io::Read::read(self, buf) {
+                Err(e) if e.kind() == std::io::ErrorKind::Interrupted => {}
+                res => return res.or(Err(CksError::Io)),
+            }
+        }
+    }
+}
+
+#[cfg(not(feature = "std"))]
+impl ByteSource for &[u8] {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize, CksError> {
+        let n = buf.len().min(self.len());
+        let (bytes, rest) = self.split_at(n);
+        buf[..n].copy_from_slice(bytes);
+        *self = rest;
+        Ok(n)
+    }
+}
+
+//reads until buf is full or the source ends, returns amount read.
+pub(crate) fn fill<S: ByteSource>(source: &mut S, buf: &mut [u8]) -> Result<usize, CksError> {
+    let mut filled = 0;
+    while filled < buf.len() {
+        match source.read(&mut buf[filled..])? {
+            0 => break,
+            n => filled += n,
+        }
+    }
+    Ok(filled)
+}
+
+//stops after the header's block count, or at the end of the input when that is unknown.
+//seeking is not available.
+pub struct ReadDecoder<S>
+where
+    S: ByteSource,
+{
+    source: S,
+    sample_info: SampleInfo,
+    blocks_left: Option<u64>,
+    reader_buf: Vec<u8>,
+    block_buf: Vec<i16>,
+    done: bool,
+}
+
+impl<S> ReadDecoder<S>
+where
+    S: ByteSource,
+{
+    //the source has to be at the start of the file.
+    pub fn new(mut source: S) -> Result<Self, CksError> {
+        let mut head = [0_u8; HEADER_BYTES];
+        if fill(&mut source, &mut head)? < HEADER_BYTES {
+            return Err(CksError::FileRead);
+        }
+        let sample_info = file_header::parse_header(&head)?;
+        let blocks_left = u64::try_from(sample_info.blocks).ok();
+        let reader_buf = Vec::with_capacity(sample_info.block_bytes as usize);
+        Ok(Self {
+            source,
+            sample_info,
+            blocks_left,
+            reader_buf,
+            block_buf: Vec::new(),
+            done: false,
+        })
+    }
+
+    pub fn sample_info(&self) -> SampleInfo {
+        self.sample_info.clone()
+    }
+
+    //decodes the next block into interleaved f32 whatever the stored format is.
+    //returns amount of frames decoded, None at the end of the stream.
+    //data ending before the header's block count is an error, a short last block is only
+    //decoded when the count is unknown.
+    pub fn decode_f32(&mut self, buf: &mut Vec<f32>) -> Result<Option<usize>, CksError> {
+        if self.done || self.blocks_left == Some(0) {
+            return Ok(None);
+        }
+        let block_bytes = self.sample_info.block_bytes as usize;
+        self.reader_buf.resize(block_bytes, 0);
+        //a failed read leaves the source somewhere inside the block, so nothing after it is read.
+        let filled =
+            fill(&mut self.source, &mut self.reader_buf).inspect_err(|_| self.done = true)?;
+        if filled < block_bytes {
+            self.done = true;
+            if self.blocks_left.is_some() {
+                return Err(CksError::InsufficientData);
+            }
+        }
+        if let Some(left) = self.blocks_left.as_mut() {
+            *left -= 1;
+        }
+        let frames = block::decode_blocks_f32(
+            &self.sample_info.format,
+            self.sample_info.channels as usize,
+            block_bytes,
+            &self.reader_buf[..filled],
+            buf,
+            &mut self.block_buf,
+        )?;
+        Ok((frames > 0).then_some(frames))
+    }
+
+    pub fn into_inner(self) -> S {
+        self.source
+    }
+}
+
+//hands out a few bytes per read and cannot seek, like a pipe.
+#[cfg(all(test, feature = "std"))]
+struct Pipe(std::io::Cursor<Vec<u8>>);
+
+#[cfg(all(test, feature = "std"))]
+impl std::io::Read for Pipe {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let n = buf.len().min(5);
+        std::io::Read::read(&mut self.0, &mut buf[..n])
+    }
+}
+
+#[cfg(test)]
+fn decode_all<S: ByteSource>(mut decoder: ReadDecoder<S>) -> Vec<f32> {
+    let mut out = Vec::new();
+    let mut buf = Vec::new();
+    while decoder.decode_f32(&mut buf).unwrap().is_some() {
+        out.extend_from_slice(&buf);
+    }
+    out
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn read_decoder_matches_seekable_decoder() {
+    let file = crate::test_util::adpcm_cks(2, &[[100, 200], [-300, 400], [500, 600], [0, 7]]);
+    let mut expected = Vec::new();
+    let mut buf = Vec::new();
+    let mut seekable = crate::decoder::Decoder::new(std::io::Cursor::new(file.clone())).unwrap();
+    while seekable.decode_f32(&mut buf).unwrap().is_some() {
+        expected.extend_from_slice(&buf);
+    }
+    let decoder = ReadDecoder::new(Pipe(std::io::Cursor::new(file))).unwrap();
+    assert_eq!(decoder.sample_info().channels, 2);
+    assert_eq!(decode_all(decoder), expected);
+    let not_cks = ReadDecoder::new(Pipe(std::io::Cursor::new(vec![0; 64])));
+    assert!(matches!(not_cks, Err(CksError::NotCksFile)));
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn read_decoder_uses_block_count_or_eof() {
+    let samples = (0..40).map(|i| i * 500).collect::<Vec<i16>>();
+    let mut file = crate::test_util::pcm16_cks(1, 8000, &samples, (0, 0, 0));
+    //trailing bytes past the last block are ignored.
+    file.extend_from_slice(&[1, 2, 3, 4]);
+    let decoded = decode_all(ReadDecoder::new(Pipe(std::io::Cursor::new(file.clone()))).unwrap());
+    assert_eq!(decoded.len(), 40);
+    assert_eq!(decoded[1], 500.0 / i16::MAX as f32);
+    //with an unknown block count everything up to the end is decoded.
+    file[20..24].copy_from_slice(&(-1_i32).to_le_bytes());
+    let decoded = decode_all(ReadDecoder::new(Pipe(std::io::Cursor::new(file))).unwrap());
+    assert_eq!(decoded.len(), 42);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn read_decoder_reports_truncation_and_read_errors() {
+    let file = crate::test_util::adpcm_cks(1, &[[100, 200], [-300, 400]]);
+    let mut decoder = ReadDecoder::new(&file[..file.len() - 10]).unwrap();
+    let mut buf = Vec::new();
+    assert_eq!(decoder.decode_f32(&mut buf).unwrap(), Some(36));
+    assert!(matches!(
+        decoder.decode_f32(&mut buf),
+        Err(CksError::InsufficientData)
+    ));
+    assert_eq!(decoder.decode_f32(&mut buf).unwrap(), None);
+
+    //fails once the header has been read.
+    struct Broken(usize);
+    impl std::io::Read for Broken {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            let file = crate::test_util::adpcm_cks(1, &[[100, 200]]);
+            let n = buf.len().min(HEADER_BYTES.saturating_sub(self.0));
+            if n == 0 {
+                return Err(std::io::ErrorKind::ConnectionReset.into());
+            }
+            buf[..n].copy_from_slice(&file[self.0..self.0 + n]);
+            self.0 += n;
+            Ok(n)
+        }
+    }
+    let mut decoder = ReadDecoder::new(Broken(0)).unwrap();
+    assert!(matches!(decoder.decode_f32(&mut buf), Err(CksError::Io)));
+}
+
+#[test]
+fn read_decoder_reads_slices() {
+    let file = crate::test_util::adpcm_cks(1, &[[100, 200], [-300, 400]]);
+    let mut decoder = ReadDecoder::new(&file[..]).unwrap();
+    let mut buf = Vec::new();
+    assert_eq!(decoder.decode_f32(&mut buf).unwrap(), Some(36));
+    assert_eq!(buf[..2], [100.0 / i16::MAX as f32, 200.0 / i16::MAX as f32]);
+    assert_eq!(decoder.decode_f32(&mut buf).unwrap(), Some(36));
+    assert_eq!(buf[0], -300.0 / i16::MAX as f32);
+    assert_eq!(decoder.decode_f32(&mut buf).unwrap(), None);
+    assert!(decoder.into_inner().is_empty());
+    assert!(matches!(
+        ReadDecoder::new(&file[..20]),
+        Err(CksError::FileRead)
+    ));
+}
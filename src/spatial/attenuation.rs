@@ -0,0 +1,42 @@
+//distance models, clamped between the emitter's min and max distance like openal's clamped models.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum AttenuationModel {
+    None,
+    Inverse,
+    Linear,
+    Exponential,
+}
+
+impl AttenuationModel {
+    //gain at the given distance; 1.0 at or inside min_distance.
+    pub fn gain(&self, distance: f32, min_distance: f32, max_distance: f32, rolloff: f32) -> f32 {
+        let min = min_distance.max(1e-6);
+        let max = max_distance.max(min);
+        let d = distance.clamp(min, max);
+        let gain = match self {
+            AttenuationModel::None => 1.0,
+            AttenuationModel::Inverse => min / (min + rolloff * (d - min)),
+            AttenuationModel::Linear => {
+                if max > min {
+                    1.0 - rolloff * (d - min) / (max - min)
+                } else {
+                    1.0
+                }
+            }
+            AttenuationModel::Exponential => (d / min).powf(-rolloff),
+        };
+        gain.clamp(0.0, 1.0)
+    }
+}
+
+#[test]
+fn attenuation_models() {
+    use AttenuationModel::*;
+    assert_eq!(Inverse.gain(0.5, 1.0, 100.0, 1.0), 1.0);
+    assert_eq!(Inverse.gain(4.0, 1.0, 100.0, 1.0), 0.25);
+    assert_eq!(Inverse.gain(400.0, 1.0, 100.0, 1.0), 0.01);
+    assert_eq!(Linear.gain(6.0, 2.0, 10.0, 1.0), 0.5);
+    assert_eq!(Linear.gain(20.0, 2.0, 10.0, 1.0), 0.0);
+    assert_eq!(Exponential.gain(4.0, 1.0, 100.0, 2.0), 1.0 / 16.0);
+    assert_eq!(None.gain(50.0, 1.0, 100.0, 1.0), 1.0);
+}
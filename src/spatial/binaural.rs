@@ -0,0 +1,284 @@
+//headphone rendering of mono sources from a spherical head model, no hrir data needed:
+//woodworth interaural delay, a broadband level difference and the brown-duda head shadow filter.
+use std::io::{Read, Seek};
+
+use super::SPEED_OF_SOUND;
+use crate::stream::SampleStream;
+
+static HEAD_RADIUS: f32 = 0.0875;
+//level difference between the ears for a source fully to one side.
+static ILD_DB: f32 = 6.0;
+//shadow filter at the ear facing away: high frequencies down to ALPHA_MIN, deepest at THETA_MIN degrees.
+static ALPHA_MIN: f32 = 0.1;
+static THETA_MIN: f32 = 150.0;
+
+//one-pole one-zero head shadow for one ear, bilinear transform of (1 + a s/2w0) / (1 + s/2w0).
+#[derive(Clone, Copy, Default)]
+struct Shadow {
+    b0: f32,
+    b1: f32,
+    a1: f32,
+    x1: f32,
+    y1: f32,
+}
+
+impl Shadow {
+    //theta is the angle between the source and the ear's axis in degrees.
+    fn set(&mut self, theta: f32, sample_rate: u32) {
+        let alpha = (1.0 + ALPHA_MIN / 2.0)
+            + (1.0 - ALPHA_MIN / 2.0) * (theta / THETA_MIN * std::f32::consts::PI).cos();
+        let w0 = SPEED_OF_SOUND / HEAD_RADIUS;
+        let tk = 2.0 * sample_rate as f32 / (2.0 * w0);
+        self.b0 = (1.0 + alpha * tk) / (1.0 + tk);
+        self.b1 = (1.0 - alpha * tk) / (1.0 + tk);
+        self.a1 = (1.0 - tk) / (1.0 + tk);
+    }
+
+    fn process(&mut self, x: f32) -> f32 {
+        let y = self.b0 * x + self.b1 * self.x1 - self.a1 * self.y1;
+        self.x1 = x;
+        self.y1 = y;
+        y
+    }
+}
+
+pub struct Binaural {
+    azimuth: f32,
+    elevation: f32,
+    sample_rate: u32,
+    history: Vec<f32>,
+    pos: usize,
+    //delay of each ear in frames as of the end of the last block; glided across the next one.
+    delays: [f32; 2],
+    gains: [f32; 2],
+    shadows: [Shadow; 2],
+    primed: bool,
+}
+
+impl Default for Binaural {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Binaural {
+    pub fn new() -> Self {
+        Self {
+            azimuth: 0.0,
+            elevation: 0.0,
+            sample_rate: 0,
+            history: Vec::new(),
+            pos: 0,
+            delays: [0.0; 2],
+            gains: [1.0; 2],
+            shadows: [Shadow::default(); 2],
+            primed: false,
+        }
+    }
+
+    pub fn azimuth(&self) -> f32 {
+        self.azimuth
+    }
+
+    pub fn elevation(&self) -> f32 {
+        self.elevation
+    }
+
+    //radians, same convention as SpatialParams: azimuth 0 ahead and positive to the right.
+    pub fn set_direction(&mut self, azimuth: f32, elevation: f32) {
+        self.azimuth = azimuth;
+        self.elevation = elevation;
+    }
+
+    pub fn reset(&mut self) {
+        self.history.iter_mut().for_each(|s| *s = 0.0);
+        self.shadows = [Shadow::default(); 2];
+        self.primed = false;
+    }
+
+    //left and right delays in frames, gains and shadow angles for the current direction.
+    fn ears(&self, sample_rate: u32) -> ([f32; 2], [f32; 2], [f32; 2]) {
+        //sine of the angle off the median plane, positive to the right.
+        let lateral = (self.azimuth.sin() * self.elevation.cos()).clamp(-1.0, 1.0);
+        let theta = lateral.asin();
+        let itd = HEAD_RADIUS / SPEED_OF_SOUND * (theta.abs() + lateral.abs());
+        let far_delay = itd * sample_rate as f32;
+        let far_gain = 10.0_f32.powf(-ILD_DB * lateral.abs() / 20.0);
+        let angle_to = |axis: f32| (axis.clamp(-1.0, 1.0)).acos().to_degrees();
+        let shadow = [angle_to(-lateral), angle_to(lateral)];
+        if lateral >= 0.0 {
+            ([far_delay, 0.0], [far_gain, 1.0], shadow)
+        } else {
+            ([0.0, far_delay], [1.0, far_gain], shadow)
+        }
+    }
+
+    fn read_delayed(&self, delay: f32) -> f32 {
+        let len = self.history.len();
+        let whole = delay.floor() as usize;
+        let frac = delay - whole as f32;
+        let a = self.history[(self.pos + len - 1 - whole) % len];
+        let b = self.history[(self.pos + 2 * len - 2 - whole) % len];
+        a + (b - a) * frac
+    }
+
+    //renders mono input into stereo interleaved output, which must hold twice as many samples.
+    pub fn process(&mut self, mono: &[f32], out: &mut [f32], sample_rate: u32) {
+        if self.sample_rate != sample_rate || self.history.is_empty() {
+            self.sample_rate = sample_rate;
+            let max_delay = (std::f32::consts::FRAC_PI_2 + 1.0) * HEAD_RADIUS / SPEED_OF_SOUND
+                * sample_rate as f32;
+            self.history = vec![0.0; max_delay.ceil() as usize + 3];
+            self.pos = 0;
+            self.primed = false;
+        }
+        let (delays, gains, angles) = self.ears(sample_rate);
+        for (shadow, angle) in self.shadows.iter_mut().zip(angles) {
+            shadow.set(angle, sample_rate);
+        }
+        if !self.primed {
+            self.delays = delays;
+            self.gains = gains;
+            self.primed = true;
+        }
+        let frames = std::cmp::min(mono.len(), out.len() / 2);
+        let len = self.history.len();
+        for (i, x) in mono[..frames].iter().enumerate() {
+            self.history[self.pos] = *x;
+            self.pos = (self.pos + 1) % len;
+            let t = (i + 1) as f32 / frames as f32;
+            for ear in 0..2 {
+                let delay = self.delays[ear] + (delays[ear] - self.delays[ear]) * t;
+                let gain = self.gains[ear] + (gains[ear] - self.gains[ear]) * t;
+                let s = self.read_delayed(delay);
+                out[i * 2 + ear] = self.shadows[ear].process(s) * gain;
+            }
+        }
+        self.delays = delays;
+        self.gains = gains;
+    }
+}
+
+//a decoder rendered for headphones. multichannel files are folded down to mono first.
+pub struct BinauralStream<R>
+where
+    R: Read + Seek,
+{
+    stream: SampleStream<R>,
+    binaural: Binaural,
+    scratch: Vec<f32>,
+    mono: Vec<f32>,
+}
+
+impl<R> BinauralStream<R>
+where
+    R: Read + Seek,
+{
+    pub fn new(stream: SampleStream<R>) -> Self {
+        Self {
+            stream,
+            binaural: Binaural::new(),
+            scratch: Vec::new(),
+            mono: Vec::new(),
+        }
+    }
+
+    pub fn stream_mut(&mut self) -> &mut SampleStream<R> {
+        &mut self.stream
+    }
+
+    pub fn sample_rate(&self) -> u32 {
+        self.stream.sample_rate()
+    }
+
+    pub fn set_direction(&mut self, azimuth: f32, elevation: f32) {
+        self.binaural.set_direction(azimuth, elevation);
+    }
+
+    //fills out with stereo interleaved frames, returns amount of frames written. 0 means the end.
+    pub fn read(&mut self, out: &mut [f32]) -> usize {
+        let ch = self.stream.channels();
+        let wanted = out.len() / 2;
+        self.scratch.resize(wanted * ch, 0.0);
+        let frames = self.stream.read(&mut self.scratch);
+        self.mono.clear();
+        self.mono.extend(
+            self.scratch[..frames * ch]
+                .chunks_exact(ch)
+                .map(|f| f.iter().sum::<f32>() / ch as f32),
+        );
+        let rate = self.stream.sample_rate();
+        self.binaural
+            .process(&self.mono, &mut out[..frames * 2], rate);
+        frames
+    }
+
+    pub fn into_inner(self) -> SampleStream<R> {
+        self.stream
+    }
+}
+
+#[cfg(test)]
+fn onset(out: &[f32], ear: usize) -> usize {
+    out.chunks_exact(2)
+        .position(|f| f[ear].abs() > 1e-4)
+        .unwrap()
+}
+
+#[test]
+fn binaural_delays_and_shadows_the_far_ear() {
+    let mut b = Binaural::new();
+    b.set_direction(std::f32::consts::FRAC_PI_2, 0.0);
+    let mut mono = vec![0.0; 4800];
+    mono[0] = 1.0;
+    let mut out = vec![0.0; 9600];
+    b.process(&mono, &mut out, 48000);
+    //woodworth gives (pi/2 + 1) * a / c, about 0.656ms.
+    assert_eq!(onset(&out, 1), 0);
+    assert_eq!(onset(&out, 0), 31);
+    let energy = |ear: usize| out.chunks_exact(2).map(|f| f[ear] * f[ear]).sum::<f32>();
+    assert!(energy(0) < energy(1) * 0.5);
+}
+
+#[test]
+fn binaural_is_symmetric_ahead() {
+    let mut b = Binaural::new();
+    let mono = crate::effects::sine(1000.0, 48000, 480, 1);
+    let mut out = vec![0.0; 960];
+    b.process(&mono, &mut out, 48000);
+    assert!(out.chunks_exact(2).all(|f| f[0] == f[1]));
+}
+
+#[test]
+fn head_shadow_hits_high_frequencies_harder() {
+    let ratio = |freq: f32| {
+        let mut b = Binaural::new();
+        b.set_direction(-std::f32::consts::FRAC_PI_2, 0.0);
+        let mono = crate::effects::sine(freq, 48000, 9600, 1);
+        let mut out = vec![0.0; 2 * 9600];
+        b.process(&mono, &mut out, 48000);
+        let ear = |e: usize| {
+            let s = out[4800..]
+                .chunks_exact(2)
+                .map(|f| f[e])
+                .collect::<Vec<_>>();
+            crate::effects::rms(&s)
+        };
+        ear(1) / ear(0)
+    };
+    assert!(ratio(200.0) > 0.4);
+    assert!(ratio(8000.0) < ratio(200.0) * 0.3);
+}
+
+#[test]
+fn binaural_stream_renders_a_decoder() {
+    let samples = vec![8192_i16; 2 * 500];
+    let file = crate::test_util::pcm16_cks(2, 8000, &samples, (0, 0, 0));
+    let decoder = crate::decoder::Decoder::new(std::io::Cursor::new(file)).unwrap();
+    let mut stream = BinauralStream::new(SampleStream::new(decoder));
+    stream.set_direction(0.5, 0.2);
+    let mut out = vec![0.0; 2 * 800];
+    assert_eq!(stream.read(&mut out), 500);
+    assert!(out[2 * 400] > 0.0 && out[2 * 400 + 1] > out[2 * 400]);
+    assert_eq!(stream.read(&mut out), 0);
+}
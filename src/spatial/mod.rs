@@ -0,0 +1,173 @@
+//positional audio: turns listener and emitter state into gain, pan and doppler pitch for a block.
+mod attenuation;
+pub mod binaural;
+pub mod surround;
+mod vector;
+
+pub use attenuation::AttenuationModel;
+pub use binaural::{Binaural, BinauralStream};
+pub use surround::{Speaker, SpeakerLayout};
+pub use vector::Vec3;
+
+//meters per second, matching positions in meters.
+pub static SPEED_OF_SOUND: f32 = 343.0;
+//doppler can't push the playback rate past these.
+static DOPPLER_MIN: f32 = 0.25;
+static DOPPLER_MAX: f32 = 4.0;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Listener {
+    pub position: Vec3,
+    pub velocity: Vec3,
+    pub forward: Vec3,
+    pub up: Vec3,
+}
+
+impl Default for Listener {
+    fn default() -> Self {
+        Self {
+            position: Vec3::ZERO,
+            velocity: Vec3::ZERO,
+            forward: Vec3::new(0.0, 0.0, -1.0),
+            up: Vec3::new(0.0, 1.0, 0.0),
+        }
+    }
+}
+
+impl Listener {
+    pub fn right(&self) -> Vec3 {
+        self.forward.cross(self.up).normalized()
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Emitter {
+    pub position: Vec3,
+    pub velocity: Vec3,
+    pub attenuation: AttenuationModel,
+    pub min_distance: f32,
+    pub max_distance: f32,
+    pub rolloff: f32,
+    //scales both velocities before the doppler shift, 0 turns it off.
+    pub doppler_factor: f32,
+}
+
+impl Default for Emitter {
+    fn default() -> Self {
+        Self {
+            position: Vec3::ZERO,
+            velocity: Vec3::ZERO,
+            attenuation: AttenuationModel::Inverse,
+            min_distance: 1.0,
+            max_distance: 1000.0,
+            rolloff: 1.0,
+            doppler_factor: 1.0,
+        }
+    }
+}
+
+impl Emitter {
+    pub fn at(position: Vec3) -> Self {
+        Self {
+            position,
+            ..Self::default()
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SpatialParams {
+    pub distance: f32,
+    //radians in the listener's horizontal plane, 0 ahead, positive to the right.
+    pub azimuth: f32,
+    //radians above the horizontal plane.
+    pub elevation: f32,
+    pub gain: f32,
+    //-1.0 left to 1.0 right.
+    pub pan: f32,
+    //playback rate multiplier.
+    pub doppler: f32,
+}
+
+pub fn spatialize(listener: &Listener, emitter: &Emitter) -> SpatialParams {
+    let offset = emitter.position - listener.position;
+    let distance = offset.length();
+    let dir = offset.normalized();
+    let forward = listener.forward.normalized();
+    let right = listener.right();
+    let up = right.cross(forward);
+    let (x, y, z) = (dir.dot(right), dir.dot(up), dir.dot(forward));
+    let (azimuth, elevation) = if distance > 0.0 {
+        (x.atan2(z), y.clamp(-1.0, 1.0).asin())
+    } else {
+        (0.0, 0.0)
+    };
+    let gain = emitter.attenuation.gain(
+        distance,
+        emitter.min_distance,
+        emitter.max_distance,
+        emitter.rolloff,
+    );
+    SpatialParams {
+        distance,
+        azimuth,
+        elevation,
+        gain,
+        pan: azimuth.sin() * elevation.cos(),
+        doppler: doppler(listener, emitter, dir),
+    }
+}
+
+//f' = f (c - v_listener) / (c - v_emitter), both speeds measured along the emitter-to-listener line.
+fn doppler(listener: &Listener, emitter: &Emitter, dir: Vec3) -> f32 {
+    if emitter.doppler_factor <= 0.0 || dir == Vec3::ZERO {
+        return 1.0;
+    }
+    let towards_listener = -dir;
+    let limit = SPEED_OF_SOUND * 0.99;
+    let vl =
+        (listener.velocity.dot(towards_listener) * emitter.doppler_factor).clamp(-limit, limit);
+    let ve = (emitter.velocity.dot(towards_listener) * emitter.doppler_factor).clamp(-limit, limit);
+    ((SPEED_OF_SOUND - vl) / (SPEED_OF_SOUND - ve)).clamp(DOPPLER_MIN, DOPPLER_MAX)
+}
+
+#[test]
+fn spatialize_finds_direction() {
+    let listener = Listener::default();
+    let right = spatialize(&listener, &Emitter::at(Vec3::new(2.0, 0.0, 0.0)));
+    assert!((right.azimuth - std::f32::consts::FRAC_PI_2).abs() < 1e-6);
+    assert!((right.pan - 1.0).abs() < 1e-6);
+    assert_eq!(right.gain, 0.5);
+    let ahead = spatialize(&listener, &Emitter::at(Vec3::new(0.0, 0.0, -5.0)));
+    assert_eq!((ahead.azimuth, ahead.pan, ahead.distance), (0.0, 0.0, 5.0));
+    let above = spatialize(&listener, &Emitter::at(Vec3::new(0.0, 3.0, 0.0)));
+    assert!((above.elevation - std::f32::consts::FRAC_PI_2).abs() < 1e-6);
+    assert!(above.pan.abs() < 1e-6);
+    //turning the listener right puts an emitter ahead of the world on its left.
+    let turned = Listener {
+        forward: Vec3::new(1.0, 0.0, 0.0),
+        ..Listener::default()
+    };
+    let left = spatialize(&turned, &Emitter::at(Vec3::new(0.0, 0.0, -1.0)));
+    assert!((left.pan + 1.0).abs() < 1e-6);
+}
+
+#[test]
+fn doppler_follows_relative_motion() {
+    let listener = Listener::default();
+    let mut emitter = Emitter::at(Vec3::new(0.0, 0.0, -100.0));
+    emitter.velocity = Vec3::new(0.0, 0.0, 34.3);
+    let approaching = spatialize(&listener, &emitter).doppler;
+    assert!((approaching - 1.0 / 0.9).abs() < 1e-5);
+    emitter.velocity = -emitter.velocity;
+    let leaving = spatialize(&listener, &emitter).doppler;
+    assert!((leaving - 1.0 / 1.1).abs() < 1e-5);
+    let moving = Listener {
+        velocity: Vec3::new(0.0, 0.0, -34.3),
+        ..listener
+    };
+    emitter.velocity = Vec3::ZERO;
+    assert!((spatialize(&moving, &emitter).doppler - 1.1).abs() < 1e-5);
+    emitter.doppler_factor = 0.0;
+    assert_eq!(spatialize(&moving, &emitter).doppler, 1.0);
+}
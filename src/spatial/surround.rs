@@ -0,0 +1,307 @@
+//2d vector base amplitude panning onto speaker layouts, plus the itu downmix back to stereo.
+static FRAC_1_SQRT_2: f32 = std::f32::consts::FRAC_1_SQRT_2;
+//widest angle a file pan of -1..1 covers, the front pair of the standard layouts.
+static PAN_AZIMUTH: f32 = 30.0;
+//default distance between the two channels of a stereo source.
+static STEREO_SPREAD: f32 = 60.0;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Speaker {
+    //degrees, 0 ahead and positive to the right like SpatialParams.
+    pub azimuth: f32,
+    pub lfe: bool,
+}
+
+impl Speaker {
+    const fn at(azimuth: f32) -> Self {
+        Self {
+            azimuth,
+            lfe: false,
+        }
+    }
+
+    const LFE: Speaker = Speaker {
+        azimuth: 0.0,
+        lfe: true,
+    };
+}
+
+//speakers in output channel order.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SpeakerLayout {
+    name: String,
+    speakers: Vec<Speaker>,
+    //indices of the non-lfe speakers, sorted by azimuth.
+    ring: Vec<usize>,
+}
+
+impl SpeakerLayout {
+    pub fn new(name: &str, speakers: Vec<Speaker>) -> Self {
+        let mut ring = (0..speakers.len())
+            .filter(|i| !speakers[*i].lfe)
+            .collect::<Vec<_>>();
+        ring.sort_by(|a, b| wrap(speakers[*a].azimuth).total_cmp(&wrap(speakers[*b].azimuth)));
+        Self {
+            name: name.to_string(),
+            speakers,
+            ring,
+        }
+    }
+
+    //L R
+    pub fn stereo() -> Self {
+        Self::new("stereo", vec![Speaker::at(-30.0), Speaker::at(30.0)])
+    }
+
+    //L R C LFE Ls Rs
+    pub fn surround_5_1() -> Self {
+        Self::new(
+            "5.1",
+            vec![
+                Speaker::at(-30.0),
+                Speaker::at(30.0),
+                Speaker::at(0.0),
+                Speaker::LFE,
+                Speaker::at(-110.0),
+                Speaker::at(110.0),
+            ],
+        )
+    }
+
+    //L R C LFE Lb Rb Ls Rs
+    pub fn surround_7_1() -> Self {
+        Self::new(
+            "7.1",
+            vec![
+                Speaker::at(-30.0),
+                Speaker::at(30.0),
+                Speaker::at(0.0),
+                Speaker::LFE,
+                Speaker::at(-150.0),
+                Speaker::at(150.0),
+                Speaker::at(-90.0),
+                Speaker::at(90.0),
+            ],
+        )
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn speakers(&self) -> &[Speaker] {
+        &self.speakers
+    }
+
+    pub fn channels(&self) -> usize {
+        self.speakers.len()
+    }
+
+    //power-normalized gains per output channel for a source at the azimuth in degrees.
+    pub fn vbap_gains(&self, azimuth: f32, gains: &mut [f32]) {
+        gains.iter_mut().for_each(|g| *g = 0.0);
+        let azimuth = wrap(azimuth);
+        match self.ring.len() {
+            0 => return,
+            1 => {
+                gains[self.ring[0]] = 1.0;
+                return;
+            }
+            _ => {}
+        }
+        for (n, a) in self.ring.iter().enumerate() {
+            let b = self.ring[(n + 1) % self.ring.len()];
+            let start = wrap(self.speakers[*a].azimuth);
+            let span = wrap_positive(self.speakers[b].azimuth - start);
+            let offset = wrap_positive(azimuth - start);
+            if offset > span {
+                continue;
+            }
+            //solve p = g1 l1 + g2 l2 in the horizontal plane.
+            let (p, l1, l2) = (unit(azimuth), unit(start), unit(start + span));
+            let det = l1.0 * l2.1 - l1.1 * l2.0;
+            let (g1, g2) = if span > 180.0 || det.abs() < 1e-6 {
+                //a pair facing apart (180 degrees or wider) would solve to negative gains,
+                //crossfade by angle instead.
+                let t = offset / span.max(1e-6);
+                (1.0 - t, t)
+            } else {
+                (
+                    (p.0 * l2.1 - p.1 * l2.0) / det,
+                    (l1.0 * p.1 - l1.1 * p.0) / det,
+                )
+            };
+            let (g1, g2) = (g1.max(0.0), g2.max(0.0));
+            let norm = (g1 * g1 + g2 * g2).sqrt().max(1e-9);
+            gains[*a] += g1 / norm;
+            gains[b] += g2 / norm;
+            return;
+        }
+    }
+
+    //adds a mono or stereo source into interleaved output of this layout.
+    //stereo sources are placed as two points `spread` degrees apart around the azimuth.
+    pub fn pan_into(
+        &self,
+        input: &[f32],
+        in_channels: usize,
+        azimuth: f32,
+        spread: Option<f32>,
+        out: &mut [f32],
+    ) {
+        let out_ch = self.channels();
+        let in_ch = in_channels.max(1);
+        let spread = spread.unwrap_or(STEREO_SPREAD);
+        let mut gains = vec![vec![0.0; out_ch]; in_ch];
+        if in_ch == 1 {
+            self.vbap_gains(azimuth, &mut gains[0]);
+        } else {
+            //anything past the first two channels is folded into the middle.
+            for (c, g) in gains.iter_mut().enumerate() {
+                let side = match c {
+                    0 => -0.5,
+                    1 => 0.5,
+                    _ => 0.0,
+                };
+                self.vbap_gains(azimuth + side * spread, g);
+            }
+        }
+        for (frame, dst) in input.chunks_exact(in_ch).zip(out.chunks_exact_mut(out_ch)) {
+            for (s, g) in frame.iter().zip(gains.iter()) {
+                for (o, g) in dst.iter_mut().zip(g.iter()) {
+                    *o += s * g;
+                }
+            }
+        }
+    }
+
+    //itu style fold-down: front speakers at full level on their side, centre and surrounds at -3db,
+    //lfe dropped. output can exceed 1.0, so a limiter belongs after it.
+    pub fn downmix_stereo(&self, input: &[f32], out: &mut [f32]) {
+        let coeffs = self
+            .speakers
+            .iter()
+            .map(|s| {
+                let az = wrap(s.azimuth);
+                if s.lfe {
+                    (0.0, 0.0)
+                } else if az == 0.0 || az.abs() == 180.0 {
+                    (FRAC_1_SQRT_2, FRAC_1_SQRT_2)
+                } else {
+                    let level = if az.abs() <= 45.0 { 1.0 } else { FRAC_1_SQRT_2 };
+                    if az < 0.0 {
+                        (level, 0.0)
+                    } else {
+                        (0.0, level)
+                    }
+                }
+            })
+            .collect::<Vec<_>>();
+        for (frame, dst) in input
+            .chunks_exact(self.channels().max(1))
+            .zip(out.chunks_exact_mut(2))
+        {
+            let (mut l, mut r) = (0.0, 0.0);
+            for (s, (cl, cr)) in frame.iter().zip(coeffs.iter()) {
+                l += s * cl;
+                r += s * cr;
+            }
+            dst[0] = l;
+            dst[1] = r;
+        }
+    }
+}
+
+//maps a stereo pan (SampleInfo::pan, Sound::pan) onto the front pair.
+pub fn azimuth_from_pan(pan: f32) -> f32 {
+    pan.clamp(-1.0, 1.0) * PAN_AZIMUTH
+}
+
+//-180 exclusive to 180 inclusive.
+fn wrap(deg: f32) -> f32 {
+    let d = deg.rem_euclid(360.0);
+    if d > 180.0 {
+        d - 360.0
+    } else {
+        d
+    }
+}
+
+fn wrap_positive(deg: f32) -> f32 {
+    deg.rem_euclid(360.0)
+}
+
+//x to the right, y ahead.
+fn unit(deg: f32) -> (f32, f32) {
+    let (s, c) = deg.to_radians().sin_cos();
+    (s, c)
+}
+
+#[cfg(test)]
+fn gains(layout: &SpeakerLayout, azimuth: f32) -> Vec<f32> {
+    let mut g = vec![0.0; layout.channels()];
+    layout.vbap_gains(azimuth, &mut g);
+    g
+}
+
+#[test]
+fn vbap_hits_speakers_and_keeps_power() {
+    let l51 = SpeakerLayout::surround_5_1();
+    let l71 = SpeakerLayout::surround_7_1();
+    for (layout, speaker) in [(&l51, 4), (&l51, 2), (&l71, 6), (&l71, 5)] {
+        let g = gains(layout, layout.speakers()[speaker].azimuth);
+        assert!((g[speaker] - 1.0).abs() < 1e-5);
+        assert!((g.iter().sum::<f32>() - 1.0).abs() < 1e-5);
+    }
+    for layout in [&l51, &l71] {
+        for az in (-180..180).step_by(7) {
+            let g = gains(layout, az as f32);
+            assert!((g.iter().map(|g| g * g).sum::<f32>() - 1.0).abs() < 1e-4);
+            assert_eq!(g[3], 0.0);
+        }
+    }
+    //straight behind sits between the two rear speakers.
+    let g = gains(&l51, 180.0);
+    assert!((g[4] - FRAC_1_SQRT_2).abs() < 1e-5 && (g[5] - FRAC_1_SQRT_2).abs() < 1e-5);
+    let g = gains(&l71, 15.0);
+    assert!(g[1] > 0.0 && g[2] > 0.0 && g[1] + g[2] > 1.0);
+}
+
+#[test]
+fn vbap_keeps_power_behind_a_stereo_pair() {
+    let stereo = SpeakerLayout::stereo();
+    //the gap from the right speaker round the back to the left one is 300 degrees wide.
+    for az in (30..=330).step_by(5) {
+        let g = gains(&stereo, az as f32);
+        assert!(
+            (g.iter().map(|g| g * g).sum::<f32>() - 1.0).abs() < 1e-4,
+            "{}",
+            az
+        );
+    }
+    let g = gains(&stereo, 180.0);
+    assert!((g[0] - FRAC_1_SQRT_2).abs() < 1e-5 && (g[1] - FRAC_1_SQRT_2).abs() < 1e-5);
+}
+
+#[test]
+fn stereo_sources_spread_around_the_azimuth() {
+    let layout = SpeakerLayout::surround_5_1();
+    let mut out = vec![0.0; 6];
+    layout.pan_into(&[1.0, 0.5], 2, 0.0, None, &mut out);
+    assert_eq!(out, [1.0, 0.5, 0.0, 0.0, 0.0, 0.0]);
+    let mut out = vec![0.0; 6];
+    layout.pan_into(&[1.0], 1, azimuth_from_pan(-1.0), None, &mut out);
+    assert!((out[0] - 1.0).abs() < 1e-5);
+}
+
+#[test]
+fn downmix_folds_surround_into_stereo() {
+    let layout = SpeakerLayout::surround_5_1();
+    let mut out = [0.0; 2];
+    layout.downmix_stereo(&[1.0, 0.0, 1.0, 1.0, 0.0, 1.0], &mut out);
+    assert!((out[0] - (1.0 + FRAC_1_SQRT_2)).abs() < 1e-6);
+    assert!((out[1] - 2.0 * FRAC_1_SQRT_2).abs() < 1e-6);
+    let stereo = SpeakerLayout::stereo();
+    stereo.downmix_stereo(&[0.25, 0.5], &mut out);
+    assert_eq!(out, [0.25, 0.5]);
+}
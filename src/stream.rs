@@ -0,0 +1,276 @@
+use std::io::{Read, Seek};
+
+use crate::decoder::Decoder;
+use crate::sample::info::SampleInfo;
+
+//interleaved f32 frames pulled from a decoder at any granularity, following the stored loop points.
+pub struct SampleStream<R>
+where
+    R: Read + Seek,
+{
+    decoder: Decoder<R>,
+    info: SampleInfo,
+    channels: usize,
+    block: Vec<f32>,
+    block_frames: usize,
+    block_pos: usize,
+    frame: u64,
+    loop_start: u64,
+    loop_end: Option<u64>,
+    //false when the loop points leave nothing to loop.
+    has_loop: bool,
+    loop_count: i32, // -1 means infinite
+    loops_left: i32,
+}
+
+impl<R> SampleStream<R>
+where
+    R: Read + Seek,
+{
+    pub fn new(decoder: Decoder<R>) -> Self {
+        let info = decoder.sample_info();
+        let channels = info.channels.max(1) as usize;
+        let total = total_frames(&info);
+        let loop_start = info.loop_start as u64;
+        let loop_end = clamp_loop_end((info.loop_end != 0).then_some(info.loop_end as u64), total);
+        let loop_count = info.loop_count as i32;
+        Self {
+            decoder,
+            channels,
+            block: Vec::new(),
+            block_frames: 0,
+            block_pos: 0,
+            frame: 0,
+            loop_start,
+            loop_end,
+            has_loop: has_loop(loop_start, loop_end, total),
+            loop_count,
+            loops_left: loop_count,
+            info,
+        }
+    }
+
+    pub fn sample_info(&self) -> &SampleInfo {
+        &self.info
+    }
+
+    pub fn channels(&self) -> usize {
+        self.channels
+    }
+
+    pub fn sample_rate(&self) -> u32 {
+        self.info.sample_rate as u32
+    }
+
+    //frames stored in the file, None if the header doesn't know.
+    pub fn total_frames(&self) -> Option<u64> {
+        total_frames(&self.info)
+    }
+
+    //frames this stream will produce from the start including loops, None if infinite or unknown.
+    pub fn duration_frames(&self) -> Option<u64> {
+        let total = self.total_frames()?;
+        if !self.has_loop {
+            return Some(total);
+        }
+        if self.loop_count < 0 {
+            return None;
+        }
+        let end = self.loop_end.unwrap_or(total);
+        let looped = end.saturating_sub(self.loop_start) * self.loop_count as u64;
+        Some(total + looped)
+    }
+
+    //frame of the file which is read next.
+    pub fn position(&self) -> u64 {
+        self.frame
+    }
+
+    pub fn loop_count(&self) -> i32 {
+        self.loop_count
+    }
+
+    //0 plays once, -1 loops forever. restarts the count of loops already played.
+    pub fn set_loop_count(&mut self, loop_count: i32) {
+        self.loop_count = loop_count;
+        self.loops_left = loop_count;
+    }
+
+    //an end at or before the start, or a start past the end of the file, disables looping.
+    pub fn set_loop_points(&mut self, start: u64, end: Option<u64>) {
+        let total = self.total_frames();
+        self.loop_start = start;
+        self.loop_end = clamp_loop_end(end, total);
+        self.has_loop = has_loop(start, self.loop_end, total);
+    }
+
+    pub fn seek(&mut self, frame: u64) {
+        let block_frames = self.info.block_frames as u64;
+        let block = frame.checked_div(block_frames).unwrap_or(0);
+        self.decoder.set_block_pos(block as i32);
+        self.frame = block * block_frames;
+        self.block_frames = 0;
+        self.block_pos = 0;
+        while self.frame < frame {
+            if !self.fill_block() {
+                return;
+            }
+            let skip = std::cmp::min((frame - self.frame) as usize, self.block_frames);
+            self.block_pos = skip;
+            self.frame += skip as u64;
+        }
+    }
+
+    //fills out with interleaved frames, returns amount of frames written. 0 means the end.
+    pub fn read(&mut self, out: &mut [f32]) -> usize {
+        let ch = self.channels;
+        let wanted = out.len() / ch;
+        let mut written = 0;
+        while written < wanted {
+            if let Some(end) = self.loop_end {
+                if self.looping() && self.frame >= end {
+                    self.restart_loop();
+                    continue;
+                }
+            }
+            if self.block_pos >= self.block_frames && !self.fill_block() {
+                //the end of the file closes the loop when no loop end is stored.
+                if self.looping() && self.loop_end.is_none() && self.frame > self.loop_start {
+                    self.restart_loop();
+                    continue;
+                }
+                break;
+            }
+            let mut n = std::cmp::min(self.block_frames - self.block_pos, wanted - written);
+            if let Some(end) = self.loop_end {
+                if self.looping() {
+                    n = std::cmp::min(n, end.saturating_sub(self.frame) as usize);
+                }
+            }
+            out[written * ch..(written + n) * ch]
+                .copy_from_slice(&self.block[self.block_pos * ch..(self.block_pos + n) * ch]);
+            self.block_pos += n;
+            self.frame += n as u64;
+            written += n;
+        }
+        written
+    }
+
+    pub fn into_decoder(self) -> Decoder<R> {
+        self.decoder
+    }
+
+    fn looping(&self) -> bool {
+        self.has_loop && self.loops_left != 0
+    }
+
+    fn restart_loop(&mut self) {
+        if self.loops_left > 0 {
+            self.loops_left -= 1;
+        }
+        self.seek(self.loop_start);
+    }
+
+    //a block that fails to decode ends the stream like the end of the file does.
+    fn fill_block(&mut self) -> bool {
+        match self.decoder.decode_f32(&mut self.block) {
+            Ok(Some(frames)) if frames > 0 => {
+                self.block_frames = frames;
+                self.block_pos = 0;
+                true
+            }
+            _ => {
+                self.block_frames = 0;
+                self.block_pos = 0;
+                false
+            }
+        }
+    }
+}
+
+//an end at or past the end of the file is the same as looping to the end of the file.
+fn clamp_loop_end(end: Option<u64>, total: Option<u64>) -> Option<u64> {
+    match (end, total) {
+        (Some(end), Some(total)) if end >= total => None,
+        _ => end,
+    }
+}
+
+fn has_loop(start: u64, end: Option<u64>, total: Option<u64>) -> bool {
+    end.is_none_or(|end| end > start) && total.is_none_or(|total| start < total)
+}
+
+fn total_frames(info: &SampleInfo) -> Option<u64> {
+    if info.blocks < 0 {
+        None
+    } else {
+        Some(info.blocks as u64 * info.block_frames as u64)
+    }
+}
+
+#[test]
+fn stream_reads_pcm_in_order() {
+    let samples = (0..100).map(|i| i * 100).collect::<Vec<i16>>();
+    let file = crate::test_util::pcm16_cks(2, 22050, &samples, (0, 0, 0));
+    let mut stream = SampleStream::new(Decoder::new(std::io::Cursor::new(file)).unwrap());
+    assert_eq!(stream.total_frames(), Some(50));
+    let mut out = vec![0.0; 64];
+    let mut all = Vec::new();
+    loop {
+        let n = stream.read(&mut out);
+        if n == 0 {
+            break;
+        }
+        all.extend_from_slice(&out[..n * 2]);
+    }
+    let expected = samples
+        .iter()
+        .map(|s| *s as f32 * (1.0 / i16::MAX as f32))
+        .collect::<Vec<_>>();
+    assert_eq!(all, expected);
+}
+
+#[test]
+fn stream_follows_loop_points() {
+    let samples = (0..10).collect::<Vec<i16>>();
+    let file = crate::test_util::pcm16_cks(1, 22050, &samples, (2, 5, 2));
+    let mut stream = SampleStream::new(Decoder::new(std::io::Cursor::new(file)).unwrap());
+    assert_eq!(stream.duration_frames(), Some(16));
+    let mut out = vec![0.0; 32];
+    let n = stream.read(&mut out);
+    let frames = out[..n]
+        .iter()
+        .map(|s| (s * i16::MAX as f32).round() as i16)
+        .collect::<Vec<_>>();
+    assert_eq!(frames, [0, 1, 2, 3, 4, 2, 3, 4, 2, 3, 4, 5, 6, 7, 8, 9]);
+}
+
+#[test]
+fn stream_ignores_empty_loops() {
+    let samples = (0..10).collect::<Vec<i16>>();
+    let file = crate::test_util::pcm16_cks(1, 22050, &samples, (5, 5, -1));
+    let mut stream = SampleStream::new(Decoder::new(std::io::Cursor::new(file)).unwrap());
+    assert_eq!(stream.duration_frames(), Some(10));
+    let mut out = vec![0.0; 32];
+    assert_eq!(stream.read(&mut out), 10);
+    assert_eq!(stream.read(&mut out), 0);
+    for (start, end) in [(6, Some(3)), (10, None), (12, Some(20))] {
+        stream.set_loop_points(start, end);
+        stream.seek(0);
+        assert_eq!(stream.duration_frames(), Some(10));
+        assert_eq!(stream.read(&mut out), 10);
+        assert_eq!(stream.read(&mut out), 0);
+    }
+}
+
+#[test]
+fn stream_decodes_adpcm_blocks() {
+    let file = crate::test_util::adpcm_cks(1, &[[100, 200], [300, 400]]);
+    let mut stream = SampleStream::new(Decoder::new(std::io::Cursor::new(file)).unwrap());
+    assert_eq!(stream.total_frames(), Some(72));
+    let mut out = vec![0.0; 100];
+    assert_eq!(stream.read(&mut out), 72);
+    let first = |s: f32| (s * i16::MAX as f32).round() as i16;
+    assert_eq!((first(out[0]), first(out[1])), (100, 200));
+    assert_eq!((first(out[36]), first(out[37])), (300, 400));
+}
@@ -0,0 +1,405 @@
+//cks as a symphonia container, and its block formats as a symphonia codec.
+use std::io::{Read, Seek, SeekFrom};
+
+use symphonia_core::audio::{
+    AsAudioBufferRef, AudioBuffer, AudioBufferRef, Channels, Signal, SignalSpec,
+};
+use symphonia_core::codecs::{
+    self, decl_codec_type, CodecDescriptor, CodecParameters, CodecType, DecoderOptions,
+    FinalizeResult,
+};
+use symphonia_core::errors::{
+    decode_error, end_of_stream_error, seek_error, unsupported_error, Result, SeekErrorKind,
+};
+use symphonia_core::formats::{
+    Cue, FormatOptions, FormatReader, Packet, SeekMode, SeekTo, SeekedTo, Track,
+};
+use symphonia_core::io::{MediaSource, MediaSourceStream};
+use symphonia_core::meta::{Metadata, MetadataLog};
+use symphonia_core::probe::{Descriptor, Instantiate, QueryDescriptor};
+use symphonia_core::sample::SampleFormat;
+use symphonia_core::units::TimeBase;
+use symphonia_core::{support_codec, support_format};
+
+use crate::decoder::DecoderType;
+use crate::decoder_core::block;
+use crate::file_header::FileHeader;
+use crate::sample::info::SampleInfo;
+
+//cks blocks get their own codec types, so registering CksDecoder leaves symphonia's pcm codecs alone.
+pub const CODEC_TYPE_CKS_ADPCM: CodecType = decl_codec_type(b"cksad");
+pub const CODEC_TYPE_CKS_PCM_S8: CodecType = decl_codec_type(b"cks8");
+pub const CODEC_TYPE_CKS_PCM_S16LE: CodecType = decl_codec_type(b"cks16");
+pub const CODEC_TYPE_CKS_PCM_F32LE: CodecType = decl_codec_type(b"cksf");
+
+//pcm blocks are single frames, so they are grouped into packets of about this many frames.
+static PCM_PACKET_FRAMES: u64 = 1024;
+
+pub struct CksReader {
+    reader: MediaSourceStream,
+    info: SampleInfo,
+    tracks: Vec<Track>,
+    cues: Vec<Cue>,
+    metadata: MetadataLog,
+    data_start: u64,
+    blocks_per_packet: u64,
+    next_block: u64,
+}
+
+impl QueryDescriptor for CksReader {
+    fn query() -> &'static [Descriptor] {
+        &[support_format!(
+            "cks",
+            "Cricket Audio CKS",
+            &["cks"],
+            &[],
+            &[b"ckmk"]
+        )]
+    }
+
+    fn score(context: &[u8]) -> u8 {
+        if context.starts_with(b"ckmk") {
+            255
+        } else {
+            0
+        }
+    }
+}
+
+impl CksReader {
+    fn codec_params(info: &SampleInfo) -> Result<CodecParameters> {
+        let (codec, sample_format, bits) = match info.format {
+            DecoderType::Adpcm => (CODEC_TYPE_CKS_ADPCM, SampleFormat::S16, 4),
+            DecoderType::Pcmi8 => (CODEC_TYPE_CKS_PCM_S8, SampleFormat::S8, 8),
+            DecoderType::Pcmi16 => (CODEC_TYPE_CKS_PCM_S16LE, SampleFormat::S16, 16),
+            DecoderType::Pcmf32 => (CODEC_TYPE_CKS_PCM_F32LE, SampleFormat::F32, 32),
+            DecoderType::Unknown => return unsupported_error("cks: unknown sample format"),
+        };
+        let channels = match info.channels {
+            1 => Channels::FRONT_LEFT,
+            2 => Channels::FRONT_LEFT | Channels::FRONT_RIGHT,
+            _ => return unsupported_error("cks: only mono and stereo are supported"),
+        };
+        let mut params = CodecParameters::new();
+        params
+            .for_codec(codec)
+            .with_sample_rate(info.sample_rate as u32)
+            .with_time_base(TimeBase::new(1, info.sample_rate.max(1) as u32))
+            .with_sample_format(sample_format)
+            .with_bits_per_coded_sample(bits)
+            .with_channels(channels)
+            .with_frames_per_block(info.block_frames as u64)
+            .with_max_frames_per_packet(info.block_frames as u64 * blocks_per_packet(info));
+        if info.blocks >= 0 {
+            params.with_n_frames(info.blocks as u64 * info.block_frames as u64);
+        }
+        Ok(params)
+    }
+
+    fn total_blocks(&self) -> Option<u64> {
+        if self.info.blocks < 0 {
+            None
+        } else {
+            Some(self.info.blocks as u64)
+        }
+    }
+}
+
+fn blocks_per_packet(info: &SampleInfo) -> u64 {
+    match info.format {
+        DecoderType::Adpcm => 1,
+        _ => std::cmp::max(1, PCM_PACKET_FRAMES / info.block_frames.max(1) as u64),
+    }
+}
+
+impl FormatReader for CksReader {
+    fn try_new(mut source: MediaSourceStream, _options: &FormatOptions) -> Result<Self> {
+        let header = FileHeader::new(&mut source).or(decode_error("cks: can't read header"))?;
+        if !header.is_cks() {
+            return unsupported_error("cks: missing ckmk marker");
+        }
+        let info = SampleInfo::new(&mut source);
+        if info.block_bytes == 0 || info.block_frames == 0 {
+            return decode_error("cks: empty blocks");
+        }
+        let data_start = source.stream_position()?;
+        let params = Self::codec_params(&info)?;
+        Ok(Self {
+            reader: source,
+            blocks_per_packet: blocks_per_packet(&info),
+            info,
+            tracks: vec![Track::new(0, params)],
+            cues: Vec::new(),
+            metadata: MetadataLog::default(),
+            data_start,
+            next_block: 0,
+        })
+    }
+
+    fn cues(&self) -> &[Cue] {
+        &self.cues
+    }
+
+    fn metadata(&mut self) -> Metadata<'_> {
+        self.metadata.metadata()
+    }
+
+    fn seek(&mut self, _mode: SeekMode, to: SeekTo) -> Result<SeekedTo> {
+        if !self.reader.is_seekable() {
+            return seek_error(SeekErrorKind::Unseekable);
+        }
+        let required_ts = match to {
+            SeekTo::TimeStamp { ts, .. } => ts,
+            SeekTo::Time { time, .. } => {
+                TimeBase::new(1, self.info.sample_rate.max(1) as u32).calc_timestamp(time)
+            }
+        };
+        let block_frames = self.info.block_frames as u64;
+        let block = required_ts / block_frames;
+        if matches!(self.total_blocks(), Some(total) if block >= total) {
+            return seek_error(SeekErrorKind::OutOfRange);
+        }
+        self.reader.seek(SeekFrom::Start(
+            self.data_start + block * self.info.block_bytes as u64,
+        ))?;
+        self.next_block = block;
+        Ok(SeekedTo {
+            track_id: 0,
+            required_ts,
+            actual_ts: block * block_frames,
+        })
+    }
+
+    fn tracks(&self) -> &[Track] {
+        &self.tracks
+    }
+
+    fn next_packet(&mut self) -> Result<Packet> {
+        let mut blocks = self.blocks_per_packet;
+        if let Some(total) = self.total_blocks() {
+            blocks = std::cmp::min(blocks, total.saturating_sub(self.next_block));
+        }
+        let block_bytes = self.info.block_bytes as usize;
+        let mut buf = vec![0_u8; blocks as usize * block_bytes];
+        let mut filled = 0;
+        while filled < buf.len() {
+            match self.reader.read(&mut buf[filled..])? {
+                0 => break,
+                n => filled += n,
+            }
+        }
+        let blocks = (filled / block_bytes) as u64;
+        if blocks == 0 {
+            return end_of_stream_error();
+        }
+        buf.truncate(blocks as usize * block_bytes);
+        let block_frames = self.info.block_frames as u64;
+        let packet = Packet::new_from_boxed_slice(
+            0,
+            self.next_block * block_frames,
+            blocks * block_frames,
+            buf.into_boxed_slice(),
+        );
+        self.next_block += blocks;
+        Ok(packet)
+    }
+
+    fn into_inner(self: Box<Self>) -> MediaSourceStream {
+        self.reader
+    }
+}
+
+pub struct CksDecoder {
+    params: CodecParameters,
+    format: DecoderType,
+    channels: usize,
+    block_bytes: usize,
+    samples: Vec<f32>,
+    block_buf: Vec<i16>,
+    buf: AudioBuffer<f32>,
+}
+
+impl codecs::Decoder for CksDecoder {
+    fn try_new(params: &CodecParameters, _options: &DecoderOptions) -> Result<Self> {
+        let format = match params.codec {
+            CODEC_TYPE_CKS_ADPCM => DecoderType::Adpcm,
+            CODEC_TYPE_CKS_PCM_S8 => DecoderType::Pcmi8,
+            CODEC_TYPE_CKS_PCM_S16LE => DecoderType::Pcmi16,
+            CODEC_TYPE_CKS_PCM_F32LE => DecoderType::Pcmf32,
+            _ => return unsupported_error("cks: unsupported codec"),
+        };
+        let channels = match params.channels {
+            Some(channels) => channels,
+            None => return unsupported_error("cks: channels are required"),
+        };
+        let frames_per_block = params.frames_per_block.unwrap_or(1) as usize;
+        //an adpcm block holds two raw samples and two nibbles per byte for each channel.
+        let block_bytes = match format {
+            DecoderType::Adpcm => channels.count() * (frames_per_block + 12) / 2,
+            _ => 0,
+        };
+        let spec = SignalSpec::new(params.sample_rate.unwrap_or(0), channels);
+        let capacity = params.max_frames_per_packet.unwrap_or(PCM_PACKET_FRAMES);
+        Ok(Self {
+            params: params.clone(),
+            format,
+            channels: channels.count(),
+            block_bytes,
+            samples: Vec::new(),
+            block_buf: Vec::new(),
+            buf: AudioBuffer::new(capacity, spec),
+        })
+    }
+
+    fn supported_codecs() -> &'static [CodecDescriptor] {
+        &[
+            support_codec!(CODEC_TYPE_CKS_ADPCM, "cks-adpcm", "Cricket Audio ADPCM"),
+            support_codec!(
+                CODEC_TYPE_CKS_PCM_S8,
+                "cks-pcm-s8",
+                "Cricket Audio PCM 8-bit"
+            ),
+            support_codec!(
+                CODEC_TYPE_CKS_PCM_S16LE,
+                "cks-pcm-s16",
+                "Cricket Audio PCM 16-bit"
+            ),
+            support_codec!(
+                CODEC_TYPE_CKS_PCM_F32LE,
+                "cks-pcm-f32",
+                "Cricket Audio PCM float"
+            ),
+        ]
+    }
+
+    fn reset(&mut self) {}
+
+    fn codec_params(&self) -> &CodecParameters {
+        &self.params
+    }
+
+    fn decode(&mut self, packet: &Packet) -> Result<AudioBufferRef<'_>> {
+        let block_bytes = if self.block_bytes == 0 {
+            packet.buf().len()
+        } else {
+            self.block_bytes
+        };
+        let frames = match block::decode_blocks_f32(
+            &self.format,
+            self.channels,
+            block_bytes,
+            packet.buf(),
+            &mut self.samples,
+            &mut self.block_buf,
+        ) {
+            Ok(frames) => frames,
+            Err(_) => return decode_error("cks: malformed block"),
+        };
+        if frames > self.buf.capacity() {
+            self.buf = AudioBuffer::new(frames as u64, *self.buf.spec());
+        }
+        self.buf.clear();
+        self.buf.render_reserved(Some(frames));
+        for c in 0..self.channels {
+            for (o, s) in self
+                .buf
+                .chan_mut(c)
+                .iter_mut()
+                .zip(self.samples.iter().skip(c).step_by(self.channels))
+            {
+                *o = *s;
+            }
+        }
+        Ok(self.buf.as_audio_buffer_ref())
+    }
+
+    fn finalize(&mut self) -> FinalizeResult {
+        FinalizeResult::default()
+    }
+
+    fn last_decoded(&self) -> AudioBufferRef<'_> {
+        self.buf.as_audio_buffer_ref()
+    }
+}
+
+#[cfg(test)]
+fn decode_all(file: Vec<u8>) -> (CodecParameters, Vec<f32>) {
+    use symphonia_core::codecs::CodecRegistry;
+    use symphonia_core::io::MediaSourceStreamOptions;
+    use symphonia_core::meta::MetadataOptions;
+    use symphonia_core::probe::{Hint, Probe};
+
+    let mut probe = Probe::default();
+    probe.register_all::<CksReader>();
+    let mut codecs = CodecRegistry::new();
+    codecs.register_all::<CksDecoder>();
+
+    let mss = MediaSourceStream::new(
+        Box::new(std::io::Cursor::new(file)),
+        MediaSourceStreamOptions::default(),
+    );
+    let mut format = probe
+        .format(
+            &Hint::new(),
+            mss,
+            &FormatOptions::default(),
+            &MetadataOptions::default(),
+        )
+        .unwrap()
+        .format;
+    let params = format.default_track().unwrap().codec_params.clone();
+    let mut decoder = codecs.make(&params, &DecoderOptions::default()).unwrap();
+    let mut out = Vec::new();
+    while let Ok(packet) = format.next_packet() {
+        let decoded = decoder.decode(&packet).unwrap();
+        let mut samples = symphonia_core::audio::SampleBuffer::<f32>::new(
+            decoded.capacity() as u64,
+            *decoded.spec(),
+        );
+        samples.copy_interleaved_ref(decoded);
+        out.extend_from_slice(samples.samples());
+    }
+    (params, out)
+}
+
+#[test]
+fn symphonia_probes_and_decodes_pcm() {
+    let samples = (0..3000).map(|i| (i % 200) as i16 * 10).collect::<Vec<_>>();
+    let file = crate::test_util::pcm16_cks(2, 32000, &samples, (0, 0, 0));
+    let (params, out) = decode_all(file);
+    assert_eq!(params.codec, CODEC_TYPE_CKS_PCM_S16LE);
+    assert_eq!(params.sample_rate, Some(32000));
+    assert_eq!(params.n_frames, Some(1500));
+    let factor = 1.0 / i16::MAX as f32;
+    let expected = samples
+        .iter()
+        .map(|s| *s as f32 * factor)
+        .collect::<Vec<_>>();
+    assert_eq!(out, expected);
+}
+
+#[test]
+fn symphonia_decodes_adpcm_blocks() {
+    let file = crate::test_util::adpcm_cks(2, &[[1, 2], [3, 4], [5, 6], [7, 8]]);
+    let (params, out) = decode_all(file);
+    assert_eq!(params.codec, CODEC_TYPE_CKS_ADPCM);
+    assert_eq!(out.len(), 2 * 72);
+    let first = |s: f32| (s * i16::MAX as f32).round() as i16;
+    assert_eq!(
+        out[..4].iter().map(|s| first(*s)).collect::<Vec<_>>(),
+        [1, 3, 2, 4]
+    );
+    assert_eq!(first(out[72]), 5);
+}
+
+#[test]
+fn symphonia_keeps_standard_pcm_codecs() {
+    use symphonia_core::codecs::{CodecRegistry, CODEC_TYPE_PCM_S16LE};
+
+    let mut codecs = CodecRegistry::new();
+    codecs.register_all::<CksDecoder>();
+    assert!(codecs.get_codec(CODEC_TYPE_PCM_S16LE).is_none());
+    assert!(codecs.get_codec(CODEC_TYPE_CKS_PCM_S16LE).is_some());
+    assert_eq!(CksReader::score(b"ckmk\x01\0\0\0"), 255);
+    assert_eq!(CksReader::score(b"RIFF\0\0\0\0"), 0);
+}
@@ -0,0 +1,88 @@
+//in-memory cks files for tests.
+use crate::sample::info::{DecoderType, SampleInfo};
+use alloc::{vec, vec::Vec};
+
+pub(crate) fn cks_file(info: &SampleInfo, data: &[u8]) -> Vec<u8> {
+    let mut file = Vec::new();
+    file.extend_from_slice(b"ckmk");
+    file.extend_from_slice(&0_u32.to_le_bytes()); // targets
+    file.extend_from_slice(&0_u32.to_le_bytes()); // file type
+    file.extend_from_slice(&2_u32.to_le_bytes()); // file version
+    let format: u8 = match info.format {
+        DecoderType::Pcmi16 => 0,
+        DecoderType::Pcmi8 => 1,
+        DecoderType::Adpcm => 2,
+        DecoderType::Pcmf32 => 3,
+        DecoderType::Unknown => 0xFF,
+    };
+    file.push(format);
+    file.push(info.channels);
+    file.extend_from_slice(&info.sample_rate.to_le_bytes());
+    file.extend_from_slice(&info.blocks.to_le_bytes());
+    file.extend_from_slice(&info.block_bytes.to_le_bytes());
+    file.extend_from_slice(&info.block_frames.to_le_bytes());
+    file.extend_from_slice(&info.volume.to_le_bytes());
+    file.extend_from_slice(&info.pan.to_le_bytes());
+    file.extend_from_slice(&info.loop_start.to_le_bytes());
+    file.extend_from_slice(&info.loop_end.to_le_bytes());
+    file.extend_from_slice(&info.loop_count.to_le_bytes());
+    file.extend_from_slice(&[0, 0]);
+    file.extend_from_slice(data);
+    file
+}
+
+//loops is (loop_start, loop_end, loop_count) in frames.
+pub(crate) fn pcm16_cks(
+    channels: u8,
+    sample_rate: u16,
+    samples: &[i16],
+    loops: (u32, u32, i16),
+) -> Vec<u8> {
+    let frames = samples.len() / channels as usize;
+    let info = SampleInfo {
+        format: DecoderType::Pcmi16,
+        channels,
+        sample_rate,
+        blocks: frames as i32,
+        block_bytes: 2 * channels as u16,
+        block_frames: 1,
+        volume: u16::MAX,
+        pan: 0,
+        loop_start: loops.0,
+        loop_end: loops.1,
+        loop_count: loops.2,
+    };
+    let data = samples
+        .iter()
+        .flat_map(|s| s.to_le_bytes())
+        .collect::<Vec<_>>();
+    cks_file(&info, &data)
+}
+
+//one adpcm channel block per entry, holding the two starting samples followed by silence deltas.
+//for stereo, entries alternate between left and right.
+pub(crate) fn adpcm_cks(channels: u8, channel_blocks: &[[i16; 2]]) -> Vec<u8> {
+    let info = SampleInfo {
+        format: DecoderType::Adpcm,
+        channels,
+        sample_rate: 44100,
+        blocks: (channel_blocks.len() / channels as usize) as i32,
+        block_bytes: 24 * channels as u16,
+        block_frames: 36,
+        volume: u16::MAX,
+        pan: 0,
+        loop_start: 0,
+        loop_end: 0,
+        loop_count: 0,
+    };
+    let mut data = Vec::new();
+    for [samp2, samp1] in channel_blocks {
+        let mut block = vec![0_u8; 24];
+        block[0] = 0; // predictor
+        block[1..3].copy_from_slice(&16_i16.to_le_bytes());
+        block[3..5].copy_from_slice(&samp2.to_le_bytes());
+        block[5..7].copy_from_slice(&samp1.to_le_bytes());
+        data.extend_from_slice(&block);
+    }
+    cks_file(&info, &data)
+}
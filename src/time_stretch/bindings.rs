@@ -1,7 +1,12 @@
 /* automatically generated by rust-bindgen 0.60.1 */
+// link names are left without the `\u{1}` prefix so rustc adds the platform symbol prefix itself,
+// and the sample type follows the `time-stretch-float` feature (SoundTouch's FLOAT_SAMPLES build).
 
 pub type uint = ::std::os::raw::c_uint;
+#[cfg(not(feature = "time-stretch-float"))]
 pub type soundtouch_SAMPLETYPE = ::std::os::raw::c_short;
+#[cfg(feature = "time-stretch-float")]
+pub type soundtouch_SAMPLETYPE = f32;
 #[repr(C)]
 pub struct soundtouch_FIFOSamplePipe__bindgen_vtable(::std::os::raw::c_void);
 #[repr(C)]
@@ -275,70 +280,70 @@ fn bindgen_test_layout_soundtouch_SoundTouch() {
     test_field_tempo();
 }
 extern "C" {
-    #[link_name = "\u{1}__ZN10soundtouch10SoundTouch16getVersionStringEv"]
+    #[link_name = "_ZN10soundtouch10SoundTouch16getVersionStringEv"]
     pub fn soundtouch_SoundTouch_getVersionString() -> *const ::std::os::raw::c_char;
 }
 extern "C" {
-    #[link_name = "\u{1}__ZN10soundtouch10SoundTouch12getVersionIdEv"]
+    #[link_name = "_ZN10soundtouch10SoundTouch12getVersionIdEv"]
     pub fn soundtouch_SoundTouch_getVersionId() -> uint;
 }
 extern "C" {
-    #[link_name = "\u{1}__ZN10soundtouch10SoundTouch7setRateEd"]
+    #[link_name = "_ZN10soundtouch10SoundTouch7setRateEd"]
     pub fn soundtouch_SoundTouch_setRate(this: *mut soundtouch_SoundTouch, newRate: f64);
 }
 extern "C" {
-    #[link_name = "\u{1}__ZN10soundtouch10SoundTouch8setTempoEd"]
+    #[link_name = "_ZN10soundtouch10SoundTouch8setTempoEd"]
     pub fn soundtouch_SoundTouch_setTempo(this: *mut soundtouch_SoundTouch, newTempo: f64);
 }
 extern "C" {
-    #[link_name = "\u{1}__ZN10soundtouch10SoundTouch13setRateChangeEd"]
+    #[link_name = "_ZN10soundtouch10SoundTouch13setRateChangeEd"]
     pub fn soundtouch_SoundTouch_setRateChange(this: *mut soundtouch_SoundTouch, newRate: f64);
 }
 extern "C" {
-    #[link_name = "\u{1}__ZN10soundtouch10SoundTouch14setTempoChangeEd"]
+    #[link_name = "_ZN10soundtouch10SoundTouch14setTempoChangeEd"]
     pub fn soundtouch_SoundTouch_setTempoChange(this: *mut soundtouch_SoundTouch, newTempo: f64);
 }
 extern "C" {
-    #[link_name = "\u{1}__ZN10soundtouch10SoundTouch8setPitchEd"]
+    #[link_name = "_ZN10soundtouch10SoundTouch8setPitchEd"]
     pub fn soundtouch_SoundTouch_setPitch(this: *mut soundtouch_SoundTouch, newPitch: f64);
 }
 extern "C" {
-    #[link_name = "\u{1}__ZN10soundtouch10SoundTouch15setPitchOctavesEd"]
+    #[link_name = "_ZN10soundtouch10SoundTouch15setPitchOctavesEd"]
     pub fn soundtouch_SoundTouch_setPitchOctaves(this: *mut soundtouch_SoundTouch, newPitch: f64);
 }
 extern "C" {
-    #[link_name = "\u{1}__ZN10soundtouch10SoundTouch17setPitchSemiTonesEi"]
+    #[link_name = "_ZN10soundtouch10SoundTouch17setPitchSemiTonesEi"]
     pub fn soundtouch_SoundTouch_setPitchSemiTones(
         this: *mut soundtouch_SoundTouch,
         newPitch: ::std::os::raw::c_int,
     );
 }
 extern "C" {
-    #[link_name = "\u{1}__ZN10soundtouch10SoundTouch17setPitchSemiTonesEd"]
+    #[link_name = "_ZN10soundtouch10SoundTouch17setPitchSemiTonesEd"]
     pub fn soundtouch_SoundTouch_setPitchSemiTones1(
         this: *mut soundtouch_SoundTouch,
         newPitch: f64,
     );
 }
 extern "C" {
-    #[link_name = "\u{1}__ZN10soundtouch10SoundTouch11setChannelsEj"]
+    #[link_name = "_ZN10soundtouch10SoundTouch11setChannelsEj"]
     pub fn soundtouch_SoundTouch_setChannels(this: *mut soundtouch_SoundTouch, numChannels: uint);
 }
 extern "C" {
-    #[link_name = "\u{1}__ZN10soundtouch10SoundTouch13setSampleRateEj"]
+    #[link_name = "_ZN10soundtouch10SoundTouch13setSampleRateEj"]
     pub fn soundtouch_SoundTouch_setSampleRate(this: *mut soundtouch_SoundTouch, srate: uint);
 }
 extern "C" {
-    #[link_name = "\u{1}__ZN10soundtouch10SoundTouch25getInputOutputSampleRatioEv"]
+    #[link_name = "_ZN10soundtouch10SoundTouch25getInputOutputSampleRatioEv"]
     pub fn soundtouch_SoundTouch_getInputOutputSampleRatio(this: *mut soundtouch_SoundTouch)
         -> f64;
 }
 extern "C" {
-    #[link_name = "\u{1}__ZN10soundtouch10SoundTouch5flushEv"]
+    #[link_name = "_ZN10soundtouch10SoundTouch5flushEv"]
     pub fn soundtouch_SoundTouch_flush(this: *mut soundtouch_SoundTouch);
 }
 extern "C" {
-    #[link_name = "\u{1}__ZN10soundtouch10SoundTouch10setSettingEii"]
+    #[link_name = "_ZN10soundtouch10SoundTouch10setSettingEii"]
     pub fn soundtouch_SoundTouch_setSetting(
         this: *mut soundtouch_SoundTouch,
         settingId: ::std::os::raw::c_int,
@@ -346,14 +351,14 @@ extern "C" {
     ) -> bool;
 }
 extern "C" {
-    #[link_name = "\u{1}__ZNK10soundtouch10SoundTouch10getSettingEi"]
+    #[link_name = "_ZNK10soundtouch10SoundTouch10getSettingEi"]
     pub fn soundtouch_SoundTouch_getSetting(
         this: *const soundtouch_SoundTouch,
         settingId: ::std::os::raw::c_int,
     ) -> ::std::os::raw::c_int;
 }
 extern "C" {
-    #[link_name = "\u{1}__ZN10soundtouch10SoundTouchC1Ev"]
+    #[link_name = "_ZN10soundtouch10SoundTouchC1Ev"]
     pub fn soundtouch_SoundTouch_SoundTouch(this: *mut soundtouch_SoundTouch);
 }
 impl soundtouch_SoundTouch {
@@ -433,11 +438,18 @@ impl soundtouch_SoundTouch {
     }
 }
 extern "C" {
-    #[link_name = "\u{1}__ZN10soundtouch10SoundTouchD1Ev"]
+    #[link_name = "_ZN10soundtouch10SoundTouchD1Ev"]
     pub fn soundtouch_SoundTouch_SoundTouch_destructor(this: *mut soundtouch_SoundTouch);
 }
 extern "C" {
-    #[link_name = "\u{1}__ZN10soundtouch10SoundTouch10putSamplesEPKsj"]
+    #[cfg_attr(
+        not(feature = "time-stretch-float"),
+        link_name = "_ZN10soundtouch10SoundTouch10putSamplesEPKsj"
+    )]
+    #[cfg_attr(
+        feature = "time-stretch-float",
+        link_name = "_ZN10soundtouch10SoundTouch10putSamplesEPKfj"
+    )]
     pub fn soundtouch_SoundTouch_putSamples(
         this: *mut ::std::os::raw::c_void,
         samples: *const soundtouch_SAMPLETYPE,
@@ -445,7 +457,14 @@ extern "C" {
     );
 }
 extern "C" {
-    #[link_name = "\u{1}__ZN10soundtouch10SoundTouch14receiveSamplesEPsj"]
+    #[cfg_attr(
+        not(feature = "time-stretch-float"),
+        link_name = "_ZN10soundtouch10SoundTouch14receiveSamplesEPsj"
+    )]
+    #[cfg_attr(
+        feature = "time-stretch-float",
+        link_name = "_ZN10soundtouch10SoundTouch14receiveSamplesEPfj"
+    )]
     pub fn soundtouch_SoundTouch_receiveSamples(
         this: *mut ::std::os::raw::c_void,
         output: *mut soundtouch_SAMPLETYPE,
@@ -453,18 +472,18 @@ extern "C" {
     ) -> uint;
 }
 extern "C" {
-    #[link_name = "\u{1}__ZN10soundtouch10SoundTouch14receiveSamplesEj"]
+    #[link_name = "_ZN10soundtouch10SoundTouch14receiveSamplesEj"]
     pub fn soundtouch_SoundTouch_receiveSamples1(
         this: *mut ::std::os::raw::c_void,
         maxSamples: uint,
     ) -> uint;
 }
 extern "C" {
-    #[link_name = "\u{1}__ZN10soundtouch10SoundTouch5clearEv"]
+    #[link_name = "_ZN10soundtouch10SoundTouch5clearEv"]
     pub fn soundtouch_SoundTouch_clear(this: *mut ::std::os::raw::c_void);
 }
 extern "C" {
-    #[link_name = "\u{1}__ZNK10soundtouch10SoundTouch21numUnprocessedSamplesEv"]
+    #[link_name = "_ZNK10soundtouch10SoundTouch21numUnprocessedSamplesEv"]
     pub fn soundtouch_SoundTouch_numUnprocessedSamples(this: *mut ::std::os::raw::c_void) -> uint;
 }
 #[repr(C)]
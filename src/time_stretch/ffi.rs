@@ -1,15 +1,25 @@
 use std::os::raw::c_char;
 use std::{ffi::c_void, fmt::Write};
 
-#[cfg(debug_assertions)]
 use super::bindings::{self, *};
 
-#[cfg(not(debug_assertions))]
-use soundtouch_sys as bindings;
-
 type SamplesRead = u32;
-struct SoundTouch {
+//i16 for the integer build of SoundTouch, f32 with `time-stretch-float`.
+pub type SampleType = bindings::soundtouch_SAMPLETYPE;
+
+extern "C" {
+    //inline in SoundTouch's headers, so bindgen leaves it out. the library exports it anyway
+    //because SoundTouch's vtable refers to it.
+    #[link_name = "_ZNK10soundtouch13FIFOProcessor10numSamplesEv"]
+    fn soundtouch_FIFOProcessor_numSamples(this: *const c_void) -> u32;
+}
+
+pub struct SoundTouch {
     core: bindings::soundtouch_SoundTouch,
+    channels: usize,
+    sample_rate: u32,
+    //conversion buffer for the sample format the linked build doesn't use.
+    scratch: Vec<SampleType>,
 }
 
 impl SoundTouch {
@@ -18,13 +28,18 @@ impl SoundTouch {
             let mut s = bindings::soundtouch_SoundTouch::new();
             bindings::soundtouch_SoundTouch_setChannels(
                 &mut s as *mut soundtouch_SoundTouch,
-                channels as u32,
+                channels,
             );
             bindings::soundtouch_SoundTouch_setSampleRate(
                 &mut s as *mut soundtouch_SoundTouch,
                 sample_rate,
             );
-            Self { core: s }
+            Self {
+                core: s,
+                channels: channels.max(1) as usize,
+                sample_rate,
+                scratch: Vec::new(),
+            }
         }
     }
 
@@ -69,15 +84,113 @@ impl SoundTouch {
         }
     }
 
+    pub fn channels(&self) -> u32 {
+        self.channels as u32
+    }
+
+    pub fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    //frames which are ready to be received.
+    pub fn num_samples(&self) -> usize {
+        unsafe {
+            soundtouch_FIFOProcessor_numSamples(
+                &self.core as *const soundtouch_SoundTouch as *const c_void,
+            ) as usize
+        }
+    }
+
     pub fn flush(&mut self) {
         unsafe {
             self.core.flush();
         }
     }
 
+    //samples are interleaved; SoundTouch counts a stereo frame as one sample.
     pub fn put_samples(&mut self, samples: &[i16]) {
+        #[cfg(not(feature = "time-stretch-float"))]
+        self.put_raw(samples);
+        #[cfg(feature = "time-stretch-float")]
+        {
+            let mut scratch = std::mem::take(&mut self.scratch);
+            scratch.clear();
+            scratch.extend(samples.iter().map(|s| *s as f32 / i16::MAX as f32));
+            self.put_raw(&scratch);
+            self.scratch = scratch;
+        }
+    }
+
+    //returns amount of frames written to output_buf. max_samples counts interleaved samples.
+    pub fn receive_samples(
+        &mut self,
+        output_buf: &mut Vec<i16>,
+        max_samples: usize,
+    ) -> SamplesRead {
+        #[cfg(not(feature = "time-stretch-float"))]
+        let frames = self.receive_raw(output_buf, max_samples);
+        #[cfg(feature = "time-stretch-float")]
+        let frames = {
+            let mut scratch = std::mem::take(&mut self.scratch);
+            let frames = self.receive_raw(&mut scratch, max_samples);
+            if output_buf.len() < max_samples {
+                output_buf.resize(max_samples, 0);
+            }
+            for (o, s) in output_buf.iter_mut().zip(&scratch[..max_samples]) {
+                *o = (s * i16::MAX as f32)
+                    .round()
+                    .clamp(i16::MIN as f32, i16::MAX as f32) as i16;
+            }
+            self.scratch = scratch;
+            frames
+        };
+        frames
+    }
+
+    //lossless with `time-stretch-float`; the int build of SoundTouch goes through an i16 stage.
+    pub fn put_samples_f32(&mut self, samples: &[f32]) {
+        #[cfg(feature = "time-stretch-float")]
+        self.put_raw(samples);
+        #[cfg(not(feature = "time-stretch-float"))]
+        {
+            let mut scratch = std::mem::take(&mut self.scratch);
+            scratch.clear();
+            scratch.extend(samples.iter().map(|s| {
+                (s * i16::MAX as f32)
+                    .round()
+                    .clamp(i16::MIN as f32, i16::MAX as f32) as i16
+            }));
+            self.put_raw(&scratch);
+            self.scratch = scratch;
+        }
+    }
+
+    pub fn receive_samples_f32(
+        &mut self,
+        output_buf: &mut Vec<f32>,
+        max_samples: usize,
+    ) -> SamplesRead {
+        #[cfg(feature = "time-stretch-float")]
+        let frames = self.receive_raw(output_buf, max_samples);
+        #[cfg(not(feature = "time-stretch-float"))]
+        let frames = {
+            let mut scratch = std::mem::take(&mut self.scratch);
+            let frames = self.receive_raw(&mut scratch, max_samples);
+            if output_buf.len() < max_samples {
+                output_buf.resize(max_samples, 0.);
+            }
+            for (o, s) in output_buf.iter_mut().zip(&scratch[..max_samples]) {
+                *o = *s as f32 / i16::MAX as f32;
+            }
+            self.scratch = scratch;
+            frames
+        };
+        frames
+    }
+
+    fn put_raw(&mut self, samples: &[SampleType]) {
         unsafe {
-            let num_samples = samples.len() as u32;
+            let num_samples = (samples.len() / self.channels) as u32;
             bindings::soundtouch_SoundTouch_putSamples(
                 &mut self.core as *mut soundtouch_SoundTouch as *mut c_void,
                 samples.as_ptr(),
@@ -86,19 +199,15 @@ impl SoundTouch {
         }
     }
 
-    pub fn receive_samples(
-        &mut self,
-        output_buf: &mut Vec<i16>,
-        max_samples: usize,
-    ) -> SamplesRead {
+    fn receive_raw(&mut self, output_buf: &mut Vec<SampleType>, max_samples: usize) -> SamplesRead {
         if output_buf.len() < max_samples {
-            output_buf.resize(max_samples, 0);
+            output_buf.resize(max_samples, Default::default());
         }
         unsafe {
             bindings::soundtouch_SoundTouch_receiveSamples(
                 &mut self.core as *mut soundtouch_SoundTouch as *mut c_void,
                 output_buf.as_mut_ptr(),
-                max_samples as u32,
+                (max_samples / self.channels) as u32,
             )
         }
     }
@@ -113,3 +222,44 @@ impl Drop for SoundTouch {
         }
     }
 }
+
+//runs against whichever SoundTouch build the features select, in debug and release alike.
+#[test]
+fn soundtouch_passthrough_keeps_length() {
+    assert!(SoundTouch::get_version_id() > 0);
+    assert!(!SoundTouch::get_version_string().is_empty());
+    let mut st = SoundTouch::new(2, 44100);
+    st.set_tempo(1.0);
+    let input = vec![1000_i16; 2 * 8192];
+    st.put_samples(&input);
+    st.flush();
+    let mut buf = Vec::new();
+    let mut frames = 0;
+    loop {
+        let n = st.receive_samples(&mut buf, 4096) as usize;
+        if n == 0 {
+            break;
+        }
+        frames += n;
+    }
+    assert_eq!(frames, 8192);
+}
+
+#[test]
+fn soundtouch_f32_passthrough_keeps_length() {
+    let mut st = SoundTouch::new(1, 44100);
+    let input = vec![0.25_f32; 8192];
+    st.put_samples_f32(&input);
+    st.flush();
+    let mut buf = Vec::new();
+    let mut frames = 0;
+    loop {
+        let n = st.receive_samples_f32(&mut buf, 4096) as usize;
+        if n == 0 {
+            break;
+        }
+        assert!(buf[..n].iter().all(|s| (s - 0.25).abs() < 1e-3 || *s == 0.));
+        frames += n;
+    }
+    assert_eq!(frames, 8192);
+}
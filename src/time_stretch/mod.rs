@@ -1,4 +1,22 @@
-//this feature uses SoundTouch library. this needs the int version of it.
-#[cfg(debug_assertions)]
+//this feature uses SoundTouch library. the same checked-in bindings are used in every profile;
+//by default they match a library built with SOUNDTOUCH_INTEGER_SAMPLES, `time-stretch-float`
+//switches them to SOUNDTOUCH_FLOAT_SAMPLES, which is what an unconfigured SoundTouch build uses.
+#[cfg(feature = "time-stretch")]
+#[allow(non_snake_case, non_camel_case_types, clippy::all)]
 mod bindings;
+#[cfg(feature = "time-stretch")]
 pub mod ffi;
+//pure rust engine for targets which can't link SoundTouch (wasm, cross builds).
+#[cfg(feature = "time-stretch-native")]
+pub mod native;
+
+//the installed library is linked directly; soundtouch-sys' build script doesn't build with
+//current bindgen, and the bindings are checked in anyway.
+#[cfg(feature = "time-stretch")]
+#[link(name = "SoundTouch")]
+extern "C" {}
+
+#[cfg(feature = "time-stretch")]
+pub use ffi::SoundTouch as TimeStretch;
+#[cfg(all(feature = "time-stretch-native", not(feature = "time-stretch")))]
+pub use native::NativeStretch as TimeStretch;
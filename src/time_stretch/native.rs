@@ -0,0 +1,550 @@
+//pure rust replacement of the SoundTouch wrapper in `ffi`.
+//tempo is changed by WSOLA (overlap-add at the best matching offset), rate by linear interpolation,
+//pitch by combining both in the same way SoundTouch does.
+
+type SamplesRead = u32;
+
+static SEQUENCE_MS: f64 = 40.0;
+static SEEK_WINDOW_MS: f64 = 15.0;
+static OVERLAP_MS: f64 = 8.0;
+static VERSION_ID: u32 = 1;
+//tempo, rate and pitch are kept within these; zero would stall the stretcher.
+static MIN_FACTOR: f64 = 0.01;
+static MAX_FACTOR: f64 = 100.0;
+
+pub struct NativeStretch {
+    channels: usize,
+    sample_rate: u32,
+    virtual_rate: f64,
+    virtual_tempo: f64,
+    virtual_pitch: f64,
+    tempo: f64,
+    rate: f64,
+    stretch: Wsola,
+    transposer: Transposer,
+    stretched: Vec<f32>,
+    output: Vec<f32>,
+    //frame counters since the last flush, used to trim the flush padding.
+    samples_expected_out: f64,
+    samples_produced: u64,
+}
+
+impl NativeStretch {
+    pub fn new(channels: u32, sample_rate: u32) -> Self {
+        let channels = channels.max(1) as usize;
+        let mut s = Self {
+            channels,
+            sample_rate,
+            virtual_rate: 1.0,
+            virtual_tempo: 1.0,
+            virtual_pitch: 1.0,
+            tempo: 1.0,
+            rate: 1.0,
+            stretch: Wsola::new(channels, sample_rate),
+            transposer: Transposer::new(channels),
+            stretched: Vec::new(),
+            output: Vec::new(),
+            samples_expected_out: 0.0,
+            samples_produced: 0,
+        };
+        s.calc_effective_rate_and_tempo();
+        s
+    }
+
+    pub fn get_version_string() -> String {
+        format!("cks-dec native {}", env!("CARGO_PKG_VERSION"))
+    }
+
+    pub fn get_version_id() -> u32 {
+        VERSION_ID
+    }
+
+    //values are clamped to MIN_FACTOR..MAX_FACTOR, NaN is ignored. the same goes for tempo and pitch.
+    pub fn set_rate(&mut self, rate: f64) {
+        self.virtual_rate = clamp_factor(rate, self.virtual_rate);
+        self.calc_effective_rate_and_tempo();
+    }
+
+    pub fn set_tempo(&mut self, tempo: f64) {
+        self.virtual_tempo = clamp_factor(tempo, self.virtual_tempo);
+        self.calc_effective_rate_and_tempo();
+    }
+
+    pub fn set_pitch(&mut self, pitch: f64) {
+        self.virtual_pitch = clamp_factor(pitch, self.virtual_pitch);
+        self.calc_effective_rate_and_tempo();
+    }
+
+    pub fn channels(&self) -> u32 {
+        self.channels as u32
+    }
+
+    pub fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    //frames which are ready to be received.
+    pub fn num_samples(&self) -> usize {
+        self.output.len() / self.channels
+    }
+
+    pub fn flush(&mut self) {
+        //push silence through the pipeline until everything that was put has come out,
+        //then cut the padding off so the output length matches the input length / (tempo * rate).
+        let expected = self.samples_expected_out.round() as u64;
+        let silence = vec![0.0_f32; self.stretch.sample_req() * self.channels];
+        let mut guard = 0;
+        while self.samples_produced < expected && guard < 1024 {
+            self.process(&silence);
+            guard += 1;
+        }
+        if self.samples_produced > expected {
+            let extra = (self.samples_produced - expected) as usize * self.channels;
+            let len = self.output.len().saturating_sub(extra);
+            self.output.truncate(len);
+        }
+        self.stretch.clear();
+        self.transposer.clear();
+        self.samples_expected_out = 0.0;
+        self.samples_produced = 0;
+    }
+
+    //samples are interleaved; a stereo frame is two samples.
+    pub fn put_samples(&mut self, samples: &[i16]) {
+        let samples = samples
+            .iter()
+            .map(|s| *s as f32 / i16::MAX as f32)
+            .collect::<Vec<_>>();
+        self.put_samples_f32(&samples);
+    }
+
+    //samples are processed as f32 internally, so this path is lossless.
+    pub fn put_samples_f32(&mut self, samples: &[f32]) {
+        let frames = samples.len() / self.channels;
+        self.samples_expected_out += frames as f64 / (self.tempo * self.rate);
+        self.process(&samples[..frames * self.channels]);
+    }
+
+    //returns amount of frames written to output_buf. max_samples counts interleaved samples.
+    pub fn receive_samples(
+        &mut self,
+        output_buf: &mut Vec<i16>,
+        max_samples: usize,
+    ) -> SamplesRead {
+        if output_buf.len() < max_samples {
+            output_buf.resize(max_samples, 0);
+        }
+        let frames = std::cmp::min(max_samples / self.channels, self.num_samples());
+        let len = frames * self.channels;
+        for (o, s) in output_buf.iter_mut().zip(self.output.drain(..len)) {
+            *o = (s * i16::MAX as f32)
+                .round()
+                .clamp(i16::MIN as f32, i16::MAX as f32) as i16;
+        }
+        frames as _
+    }
+
+    pub fn receive_samples_f32(
+        &mut self,
+        output_buf: &mut Vec<f32>,
+        max_samples: usize,
+    ) -> SamplesRead {
+        if output_buf.len() < max_samples {
+            output_buf.resize(max_samples, 0.);
+        }
+        let frames = std::cmp::min(max_samples / self.channels, self.num_samples());
+        let len = frames * self.channels;
+        for (o, s) in output_buf.iter_mut().zip(self.output.drain(..len)) {
+            *o = s;
+        }
+        frames as _
+    }
+
+    fn process(&mut self, samples: &[f32]) {
+        let before = self.output.len();
+        self.stretch.put(samples);
+        self.stretch.process(&mut self.stretched);
+        self.transposer.process(&self.stretched, &mut self.output);
+        self.stretched.clear();
+        self.samples_produced += ((self.output.len() - before) / self.channels) as u64;
+    }
+
+    fn calc_effective_rate_and_tempo(&mut self) {
+        self.tempo = clamp_factor(self.virtual_tempo / self.virtual_pitch, 1.0);
+        self.rate = clamp_factor(self.virtual_pitch * self.virtual_rate, 1.0);
+        self.stretch.set_tempo(self.tempo);
+        self.transposer.rate = self.rate;
+    }
+}
+
+fn clamp_factor(value: f64, current: f64) -> f64 {
+    if value.is_nan() {
+        current
+    } else {
+        value.clamp(MIN_FACTOR, MAX_FACTOR)
+    }
+}
+
+struct Wsola {
+    channels: usize,
+    sequence_len: usize,
+    seek_len: usize,
+    overlap_len: usize,
+    tempo: f64,
+    nominal_skip: f64,
+    skip_fract: f64,
+    is_beginning: bool,
+    input: Vec<f32>,
+    mid: Vec<f32>,
+}
+
+impl Wsola {
+    fn new(channels: usize, sample_rate: u32) -> Self {
+        let ms_to_frames = |ms: f64| ((sample_rate as f64 * ms / 1000.0) as usize).max(1);
+        let overlap_len = ms_to_frames(OVERLAP_MS);
+        let mut s = Self {
+            channels,
+            sequence_len: ms_to_frames(SEQUENCE_MS).max(overlap_len * 2 + 1),
+            seek_len: ms_to_frames(SEEK_WINDOW_MS),
+            overlap_len,
+            tempo: 1.0,
+            nominal_skip: 0.0,
+            skip_fract: 0.0,
+            is_beginning: true,
+            input: Vec::new(),
+            mid: vec![0.0; overlap_len * channels],
+        };
+        s.set_tempo(1.0);
+        s
+    }
+
+    fn set_tempo(&mut self, tempo: f64) {
+        self.tempo = tempo;
+        self.nominal_skip = tempo * (self.sequence_len - self.overlap_len) as f64;
+    }
+
+    //frames needed in the input before one sequence can be processed.
+    fn sample_req(&self) -> usize {
+        std::cmp::max(
+            (self.nominal_skip + 0.5) as usize + self.overlap_len,
+            self.sequence_len,
+        ) + self.seek_len
+    }
+
+    fn put(&mut self, samples: &[f32]) {
+        self.input.extend_from_slice(samples);
+    }
+
+    fn clear(&mut self) {
+        self.input.clear();
+        self.mid.iter_mut().for_each(|s| *s = 0.0);
+        self.skip_fract = 0.0;
+        self.is_beginning = true;
+    }
+
+    fn process(&mut self, out: &mut Vec<f32>) {
+        let ch = self.channels;
+        if self.tempo == 1.0 && self.is_beginning {
+            out.append(&mut self.input);
+            return;
+        }
+        while self.input.len() / ch >= self.sample_req() {
+            let offset = if self.is_beginning {
+                0
+            } else {
+                self.seek_best_overlap_position()
+            };
+            let seq = &self.input[offset * ch..(offset + self.sequence_len) * ch];
+            let ovl = self.overlap_len * ch;
+            if self.is_beginning {
+                out.extend_from_slice(&seq[..ovl]);
+            } else {
+                for i in 0..self.overlap_len {
+                    let w = i as f32 / self.overlap_len as f32;
+                    for c in 0..ch {
+                        let k = i * ch + c;
+                        out.push(self.mid[k] * (1.0 - w) + seq[k] * w);
+                    }
+                }
+            }
+            out.extend_from_slice(&seq[ovl..seq.len() - ovl]);
+            self.mid.copy_from_slice(&seq[seq.len() - ovl..]);
+            self.is_beginning = false;
+
+            self.skip_fract += self.nominal_skip;
+            let skip = self.skip_fract as usize;
+            self.skip_fract -= skip as f64;
+            self.input.drain(..skip * ch);
+        }
+    }
+
+    //normalized cross-correlation of the stored overlap against each candidate offset.
+    fn seek_best_overlap_position(&self) -> usize {
+        let ch = self.channels;
+        let mix =
+            |buf: &[f32], frame: usize| -> f32 { buf[frame * ch..(frame + 1) * ch].iter().sum() };
+        let mid = (0..self.overlap_len)
+            .map(|i| mix(&self.mid, i))
+            .collect::<Vec<_>>();
+        let mut best_offset = 0;
+        let mut best_corr = f64::MIN;
+        for offset in 0..self.seek_len {
+            let mut corr = 0.0_f64;
+            let mut norm = 0.0_f64;
+            for (i, m) in mid.iter().enumerate() {
+                let s = mix(&self.input, offset + i) as f64;
+                corr += *m as f64 * s;
+                norm += s * s;
+            }
+            let corr = corr / norm.max(1e-9).sqrt();
+            if corr > best_corr {
+                best_corr = corr;
+                best_offset = offset;
+            }
+        }
+        best_offset
+    }
+}
+
+struct Transposer {
+    channels: usize,
+    rate: f64,
+    //read position, where 0.0 is the last frame of the previous input.
+    pos: f64,
+    prev: Vec<f32>,
+}
+
+impl Transposer {
+    fn new(channels: usize) -> Self {
+        Self {
+            channels,
+            rate: 1.0,
+            pos: 1.0,
+            prev: vec![0.0; channels],
+        }
+    }
+
+    fn clear(&mut self) {
+        self.pos = 1.0;
+        self.prev.iter_mut().for_each(|s| *s = 0.0);
+    }
+
+    fn process(&mut self, input: &[f32], out: &mut Vec<f32>) {
+        let ch = self.channels;
+        let frames = input.len() / ch;
+        if frames == 0 {
+            return;
+        }
+        if self.rate == 1.0 && self.pos == 1.0 {
+            out.extend_from_slice(&input[..frames * ch]);
+        } else {
+            while self.pos <= frames as f64 {
+                let i = self.pos.floor() as usize;
+                let frac = (self.pos - i as f64) as f32;
+                for c in 0..ch {
+                    let s0 = if i == 0 {
+                        self.prev[c]
+                    } else {
+                        input[(i - 1) * ch + c]
+                    };
+                    let s1 = if i < frames { input[i * ch + c] } else { s0 };
+                    out.push(s0 + (s1 - s0) * frac);
+                }
+                self.pos += self.rate;
+            }
+            self.pos -= frames as f64;
+        }
+        self.prev
+            .copy_from_slice(&input[(frames - 1) * ch..frames * ch]);
+    }
+}
+
+#[cfg(test)]
+fn sine(freq: f64, sample_rate: u32, frames: usize, channels: usize) -> Vec<i16> {
+    (0..frames)
+        .flat_map(|i| {
+            let s = (2.0 * std::f64::consts::PI * freq * i as f64 / sample_rate as f64).sin();
+            std::iter::repeat_n((s * 16000.0) as i16, channels)
+        })
+        .collect()
+}
+
+//energy of a single frequency over the first channel.
+#[cfg(test)]
+fn goertzel(samples: &[i16], channels: usize, freq: f64, sample_rate: u32) -> f64 {
+    let w = 2.0 * std::f64::consts::PI * freq / sample_rate as f64;
+    let coeff = 2.0 * w.cos();
+    let (mut s1, mut s2) = (0.0, 0.0);
+    for s in samples.iter().step_by(channels) {
+        let s0 = *s as f64 + coeff * s1 - s2;
+        s2 = s1;
+        s1 = s0;
+    }
+    s1 * s1 + s2 * s2 - coeff * s1 * s2
+}
+
+#[cfg(test)]
+fn run_native(input: &[i16], channels: u32, tempo: f64, pitch: f64) -> Vec<i16> {
+    let mut st = NativeStretch::new(channels, 44100);
+    st.set_tempo(tempo);
+    st.set_pitch(pitch);
+    let mut out = Vec::new();
+    let mut buf = Vec::new();
+    for chunk in input.chunks(4096) {
+        st.put_samples(chunk);
+        let n = st.receive_samples(&mut buf, 4096) as usize;
+        out.extend_from_slice(&buf[..n * channels as usize]);
+    }
+    st.flush();
+    loop {
+        let n = st.receive_samples(&mut buf, 4096) as usize;
+        if n == 0 {
+            break;
+        }
+        out.extend_from_slice(&buf[..n * channels as usize]);
+    }
+    out
+}
+
+#[test]
+fn native_tempo_changes_length() {
+    let input = sine(440.0, 44100, 44100, 2);
+    let out = run_native(&input, 2, 2.0, 1.0);
+    assert_eq!(out.len() / 2, 22050);
+    let out = run_native(&input, 2, 0.5, 1.0);
+    assert_eq!(out.len() / 2, 88200);
+}
+
+#[test]
+fn native_tempo_keeps_pitch() {
+    let input = sine(440.0, 44100, 44100, 1);
+    let out = run_native(&input, 1, 1.5, 1.0);
+    assert!(goertzel(&out, 1, 440.0, 44100) > 10.0 * goertzel(&out, 1, 660.0, 44100));
+}
+
+#[test]
+fn native_pitch_keeps_length() {
+    let input = sine(440.0, 44100, 44100, 1);
+    let out = run_native(&input, 1, 1.0, 2.0);
+    assert_eq!(out.len(), 44100);
+    assert!(goertzel(&out, 1, 880.0, 44100) > 10.0 * goertzel(&out, 1, 440.0, 44100));
+}
+
+#[test]
+fn native_clamps_degenerate_factors() {
+    let input = sine(440.0, 44100, 441, 1);
+    //a zero tempo used to stall the sequence loop, a zero pitch to overflow the input request.
+    assert_eq!(run_native(&input, 1, 0.0, 1.0).len(), 44100);
+    assert_eq!(run_native(&input, 1, 1.0, 0.0).len(), 441);
+    assert_eq!(run_native(&input, 1, -3.0, f64::INFINITY).len(), 441);
+    let mut st = NativeStretch::new(1, 44100);
+    st.set_tempo(2.0);
+    st.set_tempo(f64::NAN);
+    st.set_rate(f64::NAN);
+    st.put_samples(&sine(440.0, 44100, 44100, 1));
+    st.flush();
+    assert_eq!(st.num_samples(), 22050);
+}
+
+#[cfg(all(test, feature = "time-stretch"))]
+fn run_soundtouch(input: &[i16], channels: u32, tempo: f64, pitch: f64) -> Vec<i16> {
+    let mut st = super::ffi::SoundTouch::new(channels, 44100);
+    st.set_tempo(tempo);
+    st.set_pitch(pitch);
+    let mut out = Vec::new();
+    let mut buf = Vec::new();
+    for chunk in input.chunks(4096) {
+        st.put_samples(chunk);
+        let n = st.receive_samples(&mut buf, 4096) as usize;
+        out.extend_from_slice(&buf[..n * channels as usize]);
+    }
+    st.flush();
+    loop {
+        let n = st.receive_samples(&mut buf, 4096) as usize;
+        if n == 0 {
+            break;
+        }
+        out.extend_from_slice(&buf[..n * channels as usize]);
+    }
+    out
+}
+
+#[test]
+fn native_f32_passthrough_is_lossless() {
+    let input = (0..4096)
+        .map(|i| (i as f32 * 0.001).sin() * 0.3 + 1e-6)
+        .collect::<Vec<_>>();
+    let mut st = NativeStretch::new(1, 44100);
+    st.put_samples_f32(&input);
+    st.flush();
+    let mut out = Vec::new();
+    let n = st.receive_samples_f32(&mut out, 8192) as usize;
+    assert_eq!(&out[..n], &input[..]);
+}
+
+#[test]
+fn native_i16_scale_matches_decoder() {
+    //same scale as decode_f32, so i16 and f32 input can be mixed.
+    let input = [i16::MAX, -16384, 500, i16::MIN];
+    let mut st = NativeStretch::new(1, 44100);
+    st.put_samples(&input);
+    st.flush();
+    let mut out = Vec::new();
+    let n = st.receive_samples_f32(&mut out, 8) as usize;
+    let expected = input.map(|s| s as f32 / i16::MAX as f32);
+    assert_eq!(&out[..n], &expected[..]);
+}
+
+#[cfg(feature = "time-stretch")]
+#[test]
+fn native_matches_soundtouch() {
+    let input = sine(440.0, 44100, 44100, 2);
+    for (tempo, pitch) in [(1.5, 1.0), (0.75, 1.0), (1.0, 1.5), (1.2, 0.8)] {
+        let native = run_native(&input, 2, tempo, pitch);
+        let reference = run_soundtouch(&input, 2, tempo, pitch);
+        let (n, r) = (native.len() as f64, reference.len() as f64);
+        assert!((n - r).abs() / r < 0.01, "length {} vs {}", n, r);
+
+        //compare the energy spread over a few bins around the expected tone.
+        let expected = 440.0 * pitch;
+        let bins = [expected * 0.5, expected, expected * 1.5, expected * 2.0];
+        let profile = |s: &[i16]| {
+            let e = bins
+                .iter()
+                .map(|f| goertzel(s, 2, *f, 44100))
+                .collect::<Vec<_>>();
+            let total = e.iter().sum::<f64>();
+            e.into_iter().map(|v| v / total).collect::<Vec<_>>()
+        };
+        for (a, b) in profile(&native).iter().zip(profile(&reference).iter()) {
+            assert!((a - b).abs() < 0.1, "spectrum {} vs {}", a, b);
+        }
+    }
+}
+
+#[cfg(feature = "time-stretch")]
+#[test]
+fn native_reports_like_soundtouch() {
+    let input = sine(440.0, 44100, 8192, 2);
+    let mut native = NativeStretch::new(2, 44100);
+    let mut st = super::ffi::SoundTouch::new(2, 44100);
+    assert_eq!(
+        (native.channels(), native.sample_rate()),
+        (st.channels(), st.sample_rate())
+    );
+    native.put_samples(&input);
+    native.flush();
+    st.put_samples(&input);
+    st.flush();
+    let mut buf = Vec::new();
+    let ready = (native.num_samples(), st.num_samples());
+    let (mut native_frames, mut st_frames) = (0, 0);
+    while native.num_samples() > 0 {
+        native_frames += native.receive_samples(&mut buf, 4096) as usize;
+    }
+    while st.num_samples() > 0 {
+        st_frames += st.receive_samples(&mut buf, 4096) as usize;
+    }
+    assert!(ready.0 > 0 && ready.1 > 0);
+    assert_eq!((native_frames, st_frames), ready);
+}
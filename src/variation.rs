@@ -0,0 +1,257 @@
+//random picks from a set of sounds (footsteps, impacts) with pitch and volume offsets.
+use std::collections::VecDeque;
+use std::io::{Read, Seek};
+
+use crate::decoder::Decoder;
+use crate::error::CksError;
+use crate::mixer::{Mixer, SoundId};
+use crate::rng::Rng;
+
+//one pick: which entry, and the offsets to play it with.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Variation<'a, T> {
+    pub index: usize,
+    pub entry: &'a T,
+    //playback speed multiplier from the pitch offset.
+    pub speed: f32,
+    //gain multiplier from the volume offset.
+    pub volume: f32,
+}
+
+//entries are whatever opens a decoder: paths, byte buffers, bank keys.
+pub struct VariationContainer<T> {
+    entries: Vec<(T, f32)>,
+    avoid_repeat: usize,
+    recent: VecDeque<usize>,
+    //semitones
+    pitch_range: (f32, f32),
+    //decibels
+    volume_range: (f32, f32),
+    rng: Rng,
+}
+
+impl<T> VariationContainer<T> {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            entries: Vec::new(),
+            avoid_repeat: 0,
+            recent: VecDeque::new(),
+            pitch_range: (0.0, 0.0),
+            volume_range: (0.0, 0.0),
+            rng: Rng::new(seed),
+        }
+    }
+
+    //entries with weight 0 are never picked. returns its index.
+    pub fn add(&mut self, entry: T, weight: f32) -> usize {
+        self.entries.push((entry, weight.max(0.0)));
+        self.entries.len() - 1
+    }
+
+    pub fn entry(&self, index: usize) -> Option<&T> {
+        self.entries.get(index).map(|(e, _)| e)
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn set_weight(&mut self, index: usize, weight: f32) {
+        if let Some(e) = self.entries.get_mut(index) {
+            e.1 = weight.max(0.0);
+        }
+    }
+
+    //the last `picks` choices are not picked again, as far as the entries allow.
+    pub fn set_avoid_repeat(&mut self, picks: usize) {
+        self.avoid_repeat = picks;
+        while self.recent.len() > picks {
+            self.recent.pop_front();
+        }
+    }
+
+    pub fn set_pitch_range(&mut self, min_semitones: f32, max_semitones: f32) {
+        self.pitch_range = (min_semitones, max_semitones.max(min_semitones));
+    }
+
+    pub fn set_volume_range(&mut self, min_db: f32, max_db: f32) {
+        self.volume_range = (min_db, max_db.max(min_db));
+    }
+
+    //restarts the random sequence and forgets recent picks.
+    pub fn reseed(&mut self, seed: u64) {
+        self.rng = Rng::new(seed);
+        self.recent.clear();
+    }
+
+    pub fn pick(&mut self) -> Option<Variation<'_, T>> {
+        let playable = self.entries.iter().filter(|(_, w)| *w > 0.0).count();
+        //always leave at least one entry to choose from.
+        let window = std::cmp::min(self.avoid_repeat, playable.saturating_sub(1));
+        let skip = self
+            .recent
+            .iter()
+            .rev()
+            .take(window)
+            .copied()
+            .collect::<Vec<_>>();
+        let allowed = |i: usize, w: f32| w > 0.0 && !skip.contains(&i);
+        let total = self
+            .entries
+            .iter()
+            .enumerate()
+            .filter(|(i, (_, w))| allowed(*i, *w))
+            .map(|(_, (_, w))| *w)
+            .sum::<f32>();
+        if total <= 0.0 {
+            return None;
+        }
+        let mut target = self.rng.next_f32() * total;
+        let mut index = None;
+        for (i, (_, w)) in self.entries.iter().enumerate() {
+            if !allowed(i, *w) {
+                continue;
+            }
+            index = Some(i);
+            if target < *w {
+                break;
+            }
+            target -= w;
+        }
+        let index = index?;
+        if self.avoid_repeat > 0 {
+            self.recent.push_back(index);
+            if self.recent.len() > self.avoid_repeat {
+                self.recent.pop_front();
+            }
+        }
+        let semitones = self.rng.range(self.pitch_range.0, self.pitch_range.1);
+        let db = self.rng.range(self.volume_range.0, self.volume_range.1);
+        Some(Variation {
+            index,
+            entry: &self.entries[index].0,
+            speed: 2.0_f32.powf(semitones / 12.0),
+            volume: 10.0_f32.powf(db / 20.0),
+        })
+    }
+
+    //picks, opens and starts a variation in the mixer, with its offsets on top of the file's values.
+    //the sound is a one-shot, the mixer drops it once it finishes. returns none when no variation
+    //can be picked or the mixer has no voice for it.
+    pub fn play<R, F>(&mut self, mixer: &mut Mixer<R>, open: F) -> Result<Option<SoundId>, CksError>
+    where
+        R: Read + Seek,
+        F: FnOnce(&T) -> Result<Decoder<R>, CksError>,
+    {
+        let Some(variation) = self.pick() else {
+            return Ok(None);
+        };
+        let id = mixer.add(open(variation.entry)?);
+        let sound = mixer.sound_mut(id).unwrap();
+        sound.set_speed(sound.speed() * variation.speed);
+        sound.set_volume(sound.volume() * variation.volume);
+        sound.set_one_shot(true);
+        if !mixer.play(id) {
+            mixer.remove(id);
+            return Ok(None);
+        }
+        Ok(Some(id))
+    }
+}
+
+#[test]
+fn variations_are_weighted_and_repeatable() {
+    let mut a = VariationContainer::new(42);
+    for (name, weight) in [("a", 1.0), ("b", 3.0), ("c", 0.0)] {
+        a.add(name, weight);
+    }
+    let mut counts = [0; 3];
+    let picks = (0..4000)
+        .map(|_| {
+            let i = a.pick().unwrap().index;
+            counts[i] += 1;
+            i
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(counts[2], 0);
+    assert!((2800..3200).contains(&counts[1]));
+    a.reseed(42);
+    assert!(picks.iter().all(|i| *i == a.pick().unwrap().index));
+}
+
+#[test]
+fn variations_avoid_recent_picks() {
+    let mut c = VariationContainer::new(3);
+    for i in 0..4 {
+        c.add(i, 1.0);
+    }
+    c.set_avoid_repeat(2);
+    let picks = (0..500)
+        .map(|_| c.pick().unwrap().index)
+        .collect::<Vec<_>>();
+    assert!(picks
+        .windows(3)
+        .all(|w| w[0] != w[1] && w[1] != w[2] && w[0] != w[2]));
+    //a window as big as the set still leaves one entry to play.
+    let mut two = VariationContainer::new(3);
+    two.add("x", 1.0);
+    two.add("y", 1.0);
+    two.set_avoid_repeat(5);
+    let picks = (0..20)
+        .map(|_| two.pick().unwrap().index)
+        .collect::<Vec<_>>();
+    assert!(picks.windows(2).all(|w| w[0] != w[1]));
+}
+
+#[test]
+fn variations_randomize_pitch_and_volume() {
+    let mut c = VariationContainer::new(9);
+    c.add(vec![100_i16; 200], 1.0);
+    c.set_pitch_range(-12.0, 12.0);
+    c.set_volume_range(-6.0, 0.0);
+    for _ in 0..200 {
+        let v = c.pick().unwrap();
+        assert!((0.5..=2.0).contains(&v.speed));
+        assert!((0.5..=1.0).contains(&v.volume));
+    }
+    let mut mixer = Mixer::new(8000);
+    let id = c
+        .play(&mut mixer, |samples| {
+            let file = crate::test_util::pcm16_cks(1, 8000, samples, (0, 0, 0));
+            Decoder::new(std::io::Cursor::new(file))
+        })
+        .unwrap()
+        .unwrap();
+    let sound = mixer.sound(id).unwrap();
+    assert!(sound.is_playing() && sound.volume() < 1.0 && sound.speed() != 1.0);
+}
+
+#[test]
+fn variations_leave_the_mixer_when_done() {
+    let open = |samples: &Vec<i16>| {
+        let file = crate::test_util::pcm16_cks(1, 8000, samples, (0, 0, 0));
+        Decoder::new(std::io::Cursor::new(file))
+    };
+    let mut c = VariationContainer::new(1);
+    c.add(vec![100_i16; 200], 1.0);
+    let mut mixer = Mixer::new(8000);
+    let id = c.play(&mut mixer, open).unwrap().unwrap();
+    let mut out = vec![0.0; 2 * 100];
+    mixer.render(&mut out);
+    assert!(mixer.sound(id).is_some());
+    mixer.render(&mut out);
+    mixer.render(&mut out);
+    assert!(mixer.is_empty());
+
+    //a voice that outranks the pick keeps it from starting, and it isn't left behind.
+    let busy = mixer.add(open(&vec![100_i16; 2000]).unwrap());
+    mixer.sound_mut(busy).unwrap().set_priority(10);
+    mixer.play(busy);
+    mixer.voice_limits_mut().set_max_voices(Some(1));
+    assert!(c.play(&mut mixer, open).unwrap().is_none());
+    assert_eq!(mixer.ids().collect::<Vec<_>>(), [busy]);
+}
@@ -0,0 +1,193 @@
+//javascript bindings for decoding previews in the browser, shaped for the web audio api.
+//tests run under node with
+//`cargo test --target wasm32-unknown-unknown --no-default-features --features wasm`.
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use wasm_bindgen::prelude::*;
+
+use crate::error::CksError;
+use crate::file_header;
+use crate::sample::info::{DecoderType, SampleInfo};
+use crate::source::ReadDecoder;
+
+#[wasm_bindgen]
+pub struct CksInfo {
+    info: SampleInfo,
+}
+
+#[wasm_bindgen]
+impl CksInfo {
+    #[wasm_bindgen(getter)]
+    pub fn format(&self) -> String {
+        match self.info.format {
+            DecoderType::Adpcm => "adpcm",
+            DecoderType::Pcmi8 => "pcm8",
+            DecoderType::Pcmi16 => "pcm16",
+            DecoderType::Pcmf32 => "float32",
+            DecoderType::Unknown => "unknown",
+        }
+        .into()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn channels(&self) -> u32 {
+        self.info.channels as u32
+    }
+
+    #[wasm_bindgen(getter, js_name = sampleRate)]
+    pub fn sample_rate(&self) -> u32 {
+        self.info.sample_rate as u32
+    }
+
+    //undefined when the file doesn't store its length or it doesn't fit in a u32.
+    #[wasm_bindgen(getter)]
+    pub fn frames(&self) -> Option<u32> {
+        u32::try_from(self.info.blocks)
+            .ok()
+            .and_then(|b| b.checked_mul(self.info.block_frames as u32))
+    }
+
+    //0.0 to 1.0
+    #[wasm_bindgen(getter)]
+    pub fn volume(&self) -> f32 {
+        self.info.volume as f32 / u16::MAX as f32
+    }
+
+    //-1.0 is left, 1.0 is right.
+    #[wasm_bindgen(getter)]
+    pub fn pan(&self) -> f32 {
+        (self.info.pan as f32 / i16::MAX as f32).clamp(-1.0, 1.0)
+    }
+
+    #[wasm_bindgen(getter, js_name = loopStart)]
+    pub fn loop_start(&self) -> u32 {
+        self.info.loop_start
+    }
+
+    #[wasm_bindgen(getter, js_name = loopEnd)]
+    pub fn loop_end(&self) -> u32 {
+        self.info.loop_end
+    }
+
+    //0 plays once, -1 loops forever.
+    #[wasm_bindgen(getter, js_name = loopCount)]
+    pub fn loop_count(&self) -> i32 {
+        self.info.loop_count as i32
+    }
+}
+
+//planar samples, one array per channel as AudioBuffer.copyToChannel takes them.
+#[wasm_bindgen]
+pub struct DecodedCks {
+    sample_rate: u32,
+    channels: Vec<Vec<f32>>,
+}
+
+#[wasm_bindgen]
+impl DecodedCks {
+    #[wasm_bindgen(getter, js_name = sampleRate)]
+    pub fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    #[wasm_bindgen(getter, js_name = numberOfChannels)]
+    pub fn number_of_channels(&self) -> u32 {
+        self.channels.len() as u32
+    }
+
+    //frames per channel.
+    #[wasm_bindgen(getter)]
+    pub fn length(&self) -> u32 {
+        self.channels.first().map_or(0, |c| c.len() as u32)
+    }
+
+    //copied out as a Float32Array.
+    #[wasm_bindgen(js_name = getChannelData)]
+    pub fn channel_data(&self, channel: u32) -> Option<Vec<f32>> {
+        self.channels.get(channel as usize).cloned()
+    }
+}
+
+#[wasm_bindgen(js_name = readInfo)]
+pub fn read_info(bytes: &[u8]) -> Result<CksInfo, JsError> {
+    let info = file_header::parse_header(bytes).map_err(js_error)?;
+    Ok(CksInfo { info })
+}
+
+#[wasm_bindgen]
+pub fn decode(bytes: &[u8]) -> Result<DecodedCks, JsError> {
+    decode_planar(bytes).map_err(js_error)
+}
+
+fn decode_planar(bytes: &[u8]) -> Result<DecodedCks, CksError> {
+    let mut decoder = ReadDecoder::new(bytes)?;
+    let info = decoder.sample_info();
+    let channel_count = info.channels.max(1) as usize;
+    let mut channels = alloc::vec![Vec::new(); channel_count];
+    let mut buf = Vec::new();
+    while decoder.decode_f32(&mut buf)?.is_some() {
+        for frame in buf.chunks_exact(channel_count) {
+            for (channel, sample) in channels.iter_mut().zip(frame) {
+                channel.push(*sample);
+            }
+        }
+    }
+    Ok(DecodedCks {
+        sample_rate: info.sample_rate as u32,
+        channels,
+    })
+}
+
+fn js_error(e: CksError) -> JsError {
+    JsError::new(&format!("{:?}", e))
+}
+
+#[cfg(all(test, target_arch = "wasm32"))]
+use wasm_bindgen_test::wasm_bindgen_test as test;
+
+#[test]
+fn decode_splits_channels() {
+    let samples = [1000_i16, -1000, 2000, -2000, 3000, -3000];
+    let file = crate::test_util::pcm16_cks(2, 22050, &samples, (0, 0, 0));
+    let decoded = decode(&file).unwrap();
+    assert_eq!(decoded.sample_rate(), 22050);
+    assert_eq!(decoded.number_of_channels(), 2);
+    assert_eq!(decoded.length(), 3);
+    let right = decoded.channel_data(1).unwrap();
+    assert_eq!(right[2], -3000.0 / i16::MAX as f32);
+    assert!(decoded.channel_data(2).is_none());
+}
+
+#[test]
+fn read_info_describes_the_file() {
+    let file = crate::test_util::adpcm_cks(1, &[[100, 200], [300, 400]]);
+    let info = read_info(&file).unwrap();
+    assert_eq!(info.format(), "adpcm");
+    assert_eq!((info.channels(), info.sample_rate()), (1, 44100));
+    assert_eq!(info.frames(), Some(72));
+    assert_eq!(decode(&file).unwrap().length(), 72);
+    assert!(matches!(decode_planar(&[0; 64]), Err(CksError::NotCksFile)));
+}
+
+#[test]
+fn decode_reports_bad_files() {
+    let mut file = crate::test_util::adpcm_cks(1, &[[100, 200], [300, 400]]);
+    file[file_header::HEADER_BYTES + 24] = 9;
+    assert!(matches!(decode_planar(&file), Err(CksError::InvalidBlock)));
+    assert!(matches!(
+        decode_planar(&file[..file.len() - 1]),
+        Err(CksError::InsufficientData)
+    ));
+    //more blocks than a u32 of frames can count.
+    file[20..24].copy_from_slice(&i32::MAX.to_le_bytes());
+    assert_eq!(read_info(&file).unwrap().frames(), None);
+}
+
+//JsError only exists on the javascript side.
+#[cfg(target_arch = "wasm32")]
+#[test]
+fn errors_reach_javascript() {
+    assert!(decode(&[0; 64]).is_err());
+    assert!(read_info(&[0; 8]).is_err());
+}
@@ -0,0 +1,82 @@
+//minimal riff/wave writer for interleaved f32 renders.
+use std::io::{self, Write};
+
+fn write_header<W: Write>(
+    w: &mut W,
+    format_tag: u16,
+    bits: u16,
+    channels: u16,
+    sample_rate: u32,
+    data_bytes: u32,
+) -> io::Result<()> {
+    let block_align = channels * bits / 8;
+    w.write_all(b"RIFF")?;
+    w.write_all(&(36 + data_bytes).to_le_bytes())?;
+    w.write_all(b"WAVE")?;
+    w.write_all(b"fmt ")?;
+    w.write_all(&16_u32.to_le_bytes())?;
+    w.write_all(&format_tag.to_le_bytes())?;
+    w.write_all(&channels.to_le_bytes())?;
+    w.write_all(&sample_rate.to_le_bytes())?;
+    w.write_all(&(sample_rate * block_align as u32).to_le_bytes())?;
+    w.write_all(&block_align.to_le_bytes())?;
+    w.write_all(&bits.to_le_bytes())?;
+    w.write_all(b"data")?;
+    w.write_all(&data_bytes.to_le_bytes())
+}
+
+//16-bit pcm, samples clamped to -1..1 and rounded to the nearest step.
+pub fn write_wav_i16<W: Write>(
+    mut w: W,
+    samples: &[f32],
+    channels: u16,
+    sample_rate: u32,
+) -> io::Result<()> {
+    write_header(
+        &mut w,
+        1,
+        16,
+        channels,
+        sample_rate,
+        samples.len() as u32 * 2,
+    )?;
+    let data = samples
+        .iter()
+        .flat_map(|s| ((s.clamp(-1.0, 1.0) * i16::MAX as f32).round() as i16).to_le_bytes())
+        .collect::<Vec<_>>();
+    w.write_all(&data)
+}
+
+//32-bit ieee float, written as is.
+pub fn write_wav_f32<W: Write>(
+    mut w: W,
+    samples: &[f32],
+    channels: u16,
+    sample_rate: u32,
+) -> io::Result<()> {
+    write_header(
+        &mut w,
+        3,
+        32,
+        channels,
+        sample_rate,
+        samples.len() as u32 * 4,
+    )?;
+    let data = samples
+        .iter()
+        .flat_map(|s| s.to_le_bytes())
+        .collect::<Vec<_>>();
+    w.write_all(&data)
+}
+
+#[test]
+fn wav_i16_layout() {
+    let mut out = Vec::new();
+    write_wav_i16(&mut out, &[0.0, 1.0, -1.0, 2.0], 2, 8000).unwrap();
+    assert_eq!(out.len(), 44 + 8);
+    assert_eq!(&out[..4], b"RIFF");
+    assert_eq!(u32::from_le_bytes(out[4..8].try_into().unwrap()), 44);
+    assert_eq!(u16::from_le_bytes(out[22..24].try_into().unwrap()), 2);
+    assert_eq!(u32::from_le_bytes(out[28..32].try_into().unwrap()), 32000);
+    assert_eq!(&out[44..], &[0, 0, 0xFF, 0x7F, 0x01, 0x80, 0xFF, 0x7F]);
+}